@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+
+/// The program's command-line arguments, which are consumed piece by piece.
+///
+/// Flags and options may be taken from anywhere within the list, so they should be taken *before* any positional
+/// arguments are read.
+#[derive(Clone, Debug, Default)]
+pub struct Arguments {
+    /// The remaining, unconsumed arguments.
+    inner: VecDeque<String>,
+}
+
+impl Arguments {
+    /// Creates a new [`Arguments`] from the given list.
+    pub fn new(arguments: impl IntoIterator<Item = String>) -> Self {
+        Self { inner: arguments.into_iter().collect() }
+    }
+
+    /// Creates a new [`Arguments`] from the process' arguments, skipping the executable name.
+    pub fn from_env() -> Self {
+        Self::new(std::env::args().skip(1))
+    }
+
+    /// Returns a reference to the next positional argument without consuming it.
+    pub fn peek(&self) -> Option<&str> {
+        self.inner.front().map(String::as_str)
+    }
+
+    /// Discards the next positional argument, returning the remaining arguments.
+    pub fn skip(mut self) -> Self {
+        _ = self.inner.pop_front();
+
+        self
+    }
+
+    /// Consumes and parses the next positional argument.
+    pub fn next<T>(&mut self, name: &str) -> Result<T>
+    where
+        T: FromStr<Err: Error + Send + Sync + 'static>,
+    {
+        self.next_optional(name)?.with_context(|| format!("missing {name}"))
+    }
+
+    /// Consumes and parses the next positional argument if one is present.
+    pub fn next_optional<T>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr<Err: Error + Send + Sync + 'static>,
+    {
+        let Some(value) = self.inner.pop_front() else { return Ok(None) };
+
+        value.parse().map(Some).with_context(|| format!("invalid {name} '{value}'"))
+    }
+
+    /// Consumes the given flag, returning whether it was present.
+    pub fn flag(&mut self, name: &str) -> bool {
+        let Some(index) = self.inner.iter().position(|v| v == name) else { return false };
+
+        self.inner.remove(index).is_some()
+    }
+
+    /// Consumes and parses the value of the given option, if it was present.
+    ///
+    /// Both `--name value` and `--name=value` are accepted.
+    pub fn option<T>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr<Err: Error + Send + Sync + 'static>,
+    {
+        let Some(index) =
+            self.inner.iter().position(|v| v == name || v.strip_prefix(name).is_some_and(|v| v.starts_with('=')))
+        else {
+            return Ok(None);
+        };
+
+        // The index is guaranteed to exist, as we just found it.
+        let argument = self.inner.remove(index).unwrap();
+        let value = match argument.split_once('=') {
+            Some((_, value)) => value.to_string(),
+            None => self.inner.remove(index).with_context(|| format!("missing value for {name}"))?,
+        };
+
+        value.parse().map(Some).with_context(|| format!("invalid value for {name} '{value}'"))
+    }
+
+    /// Ensures that every argument has been consumed.
+    pub fn finish(self) -> Result<()> {
+        if self.inner.is_empty() { Ok(()) } else { bail!("unexpected arguments: {:?}", self.inner) }
+    }
+}
@@ -0,0 +1,144 @@
+use std::cmp::Reverse;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+
+use crate::Day;
+use crate::arguments::Arguments;
+use crate::outcome::Outcome;
+use crate::utility::hash::{fnv1a, fnv1a_hex};
+
+/// The directory that every cached answer is written into, found from the crate's root rather than the working
+/// directory.
+const CACHE_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/answer-cache");
+/// The environment variable that enables the cache without having to pass `--cache` every time.
+const ENABLE_VARIABLE: &str = "AOC_CACHE";
+/// The file within every build's directory whose modification time records when that build last opened the cache.
+const LAST_USED_FILE: &str = "last-used";
+/// How long a build's answers are kept around after it last opened the cache.
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// The most builds that are kept around at once, counting the current one.
+const MAX_BUILDS: usize = 16;
+
+/// A cache of previously computed answers, keyed by day, part, input, and build.
+///
+/// Every build gets its own directory, since any change to the code could change an answer. Other builds (like a debug
+/// and a release build sharing a target directory) are left alone, unless they haven't been used within the last 30
+/// days or are older than the 15 most recently used ones, so that the cache doesn't grow forever.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    /// The directory containing this build's answers.
+    directory: PathBuf,
+}
+
+impl Cache {
+    /// Opens the cache if it was enabled using either `--cache` or the `AOC_CACHE` environment variable, and wasn't
+    /// then disabled using `--no-cache`.
+    pub fn from_arguments(arguments: &mut Arguments) -> Result<Option<Self>> {
+        let enabled =
+            arguments.flag("--cache") || std::env::var(ENABLE_VARIABLE).is_ok_and(|v| !v.is_empty() && v != "0");
+        // This is taken no matter what, so that passing both flags isn't an error.
+        let disabled = arguments.flag("--no-cache");
+
+        if enabled && !disabled { Self::open().map(Some) } else { Ok(None) }
+    }
+
+    /// Opens the cache for the current build.
+    pub fn open() -> Result<Self> {
+        let parent = Path::new(CACHE_DIRECTORY);
+        let directory = parent.join(self::build_id()?);
+
+        std::fs::create_dir_all(&directory)
+            .with_context(|| format!("failed to create the cache directory '{}'", directory.display()))?;
+
+        // Bumping the marker's modification time keeps this build from being pruned by any other.
+        let marker = File::options().create(true).truncate(false).write(true).open(directory.join(LAST_USED_FILE));
+
+        _ = marker.and_then(|v| v.set_modified(SystemTime::now()));
+
+        self::prune(parent);
+
+        Ok(Self { directory })
+    }
+
+    /// Returns the cached answer for the given day, part, and input, if there is one.
+    pub fn get(&self, day: u8, part: u8, input: &str) -> Option<u64> {
+        std::fs::read_to_string(self.path(day, part, input)).ok()?.trim().parse().ok()
+    }
+
+    /// Caches the answer for the given day, part, and input.
+    ///
+    /// Failing to write to the cache isn't worth failing over, so any errors are ignored.
+    pub fn insert(&self, day: u8, part: u8, input: &str, answer: u64) {
+        _ = std::fs::write(self.path(day, part, input), format!("{answer}\n"));
+    }
+
+    /// Returns the path of the file containing the cached answer for the given day, part, and input.
+    fn path(&self, day: u8, part: u8, input: &str) -> PathBuf {
+        self.directory.join(format!("day-{day}-part-{part}-{}.txt", fnv1a_hex(input.as_bytes())))
+    }
+}
+
+/// Runs the given part of a day against the given input, returning its outcome and how long it took.
+///
+/// If a cache is given, answers are served from it whenever possible, in which case there's no duration since nothing
+/// was actually run. New answers are then added to it.
+pub fn solve(cache: Option<&Cache>, day: &Day, part: u8, input: &str) -> (Outcome, Option<Duration>) {
+    if let Some(answer) = cache.and_then(|v| v.get(day.number, part, input)) {
+        return (Outcome::Answer(answer), None);
+    }
+
+    let start = Instant::now();
+    let outcome = Outcome::of(day.solutions[part as usize - 1], input);
+    let elapsed = start.elapsed();
+
+    if let (Some(cache), Outcome::Answer(answer)) = (cache, &outcome) {
+        cache.insert(day.number, part, input, *answer);
+    }
+
+    (outcome, Some(elapsed))
+}
+
+/// Removes every build's directory that hasn't been used recently enough, or that falls outside of the most recently
+/// used builds.
+///
+/// Failing to prune the cache isn't worth failing over, so any errors are ignored.
+fn prune(parent: &Path) {
+    let Ok(entries) = std::fs::read_dir(parent) else { return };
+    let now = SystemTime::now();
+    let mut builds: Vec<(SystemTime, PathBuf)> = entries
+        .map_while(Result::ok)
+        .map(|v| v.path())
+        .filter(|v| v.is_dir())
+        .map(|v| {
+            // Anything without a marker (including directories from before there were markers) counts as ancient.
+            let used = std::fs::metadata(v.join(LAST_USED_FILE)).and_then(|v| v.modified()).unwrap_or(UNIX_EPOCH);
+
+            (used, v)
+        })
+        .collect();
+
+    // Most recently used first, so that everything past the limit is the stuff nobody has touched in a while.
+    builds.sort_unstable_by_key(|v| Reverse(v.0));
+
+    for (index, (used, path)) in builds.into_iter().enumerate() {
+        let is_stale = now.duration_since(used).is_ok_and(|v| v > MAX_AGE);
+
+        if index >= MAX_BUILDS || is_stale {
+            _ = std::fs::remove_dir_all(path);
+        }
+    }
+}
+
+/// Returns an identifier for the running executable, which changes whenever it's rebuilt.
+///
+/// Hashing the entire executable would be more precise, but it's also painfully slow for debug builds, so we settle
+/// for its size and modification time instead. The worst that can happen is an unnecessary cache miss.
+fn build_id() -> Result<String> {
+    let metadata = std::env::current_exe().and_then(std::fs::metadata).context("failed to inspect the executable")?;
+    let modified = metadata.modified()?.duration_since(UNIX_EPOCH).map_or(0, |v| v.as_nanos());
+
+    Ok(format!("{:016x}", fnv1a(format!("{}:{modified}", metadata.len()).as_bytes())))
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Write, stdout};
+use std::num::NonZeroUsize;
+use std::panic::{set_hook, take_hook};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+
+use crate::arguments::Arguments;
+use crate::cache::{self, Cache};
+use crate::outcome::Outcome;
+use crate::{Day, inputs};
+
+/// Runs both parts of a day against every file within a directory, printing one row of results per file.
+///
+/// Files are solved in parallel, so timings are only as reliable as the machine is idle.
+///
+/// Usage: `batch <day #> <directory> [--threads <N>] [--answers <file>] [--cache | --no-cache]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let threads: Option<NonZeroUsize> = arguments.option("--threads")?;
+    let answers_path: Option<PathBuf> = arguments.option("--answers")?;
+    let cache = Cache::from_arguments(&mut arguments)?;
+    let day = Day::find(arguments.next("day")?)?;
+    let directory: PathBuf = arguments.next("directory")?;
+
+    arguments.finish()?;
+
+    let answers = match &answers_path {
+        Some(path) => Some(self::read_answers(path)?),
+        None => None,
+    };
+    let paths = self::list_inputs(&directory, answers_path.as_deref())?;
+
+    if paths.is_empty() {
+        bail!("'{}' does not contain any input files", directory.display());
+    }
+
+    let threads = threads.or_else(|| std::thread::available_parallelism().ok()).map_or(1, NonZeroUsize::get);
+    let next = AtomicUsize::new(0);
+
+    // Solutions are allowed to panic here, but their messages belong in the table rather than all over the terminal.
+    let hook = take_hook();
+
+    set_hook(Box::new(|_| {}));
+
+    // The simplest thread pool there is: every worker keeps grabbing the next file until there are none left.
+    let mut rows: Vec<Row> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0 .. threads.min(paths.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut rows = Vec::new();
+
+                    while let Some(path) = paths.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        rows.push(Row::solve(day, path, cache.as_ref()));
+                    }
+
+                    rows
+                })
+            })
+            .collect();
+
+        // Panics are already caught while solving, so a worker can't actually fail to join.
+        workers.into_iter().flat_map(|v| v.join().unwrap_or_default()).collect()
+    });
+
+    set_hook(hook);
+
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut table = vec![["file", "part 1", "time", "part 2", "time", "check"].map(str::to_string).to_vec()];
+    let (mut failures, mut mismatches) = (0, 0);
+
+    for row in &rows {
+        let check = answers.as_ref().map(|v| row.check(v.get(&row.name)));
+
+        failures += usize::from(row.parts.iter().any(|(v, _)| !matches!(v, Outcome::Answer(_))));
+        mismatches += usize::from(check.is_some_and(|v| v == Check::Mismatch));
+
+        let mut cells = vec![row.name.clone()];
+
+        for (outcome, elapsed) in &row.parts {
+            cells.push(match outcome {
+                Outcome::Answer(answer) => answer.to_string(),
+                Outcome::Error(error) => format!("error: {error}"),
+                Outcome::Panic(message) => format!("panic: {message}"),
+            });
+            cells.push(elapsed.map_or_else(|| "cached".to_string(), |v| format!("{v:.2?}")));
+        }
+
+        cells.push(check.map_or_else(|| "-".to_string(), |v| v.to_string()));
+        table.push(cells);
+    }
+
+    if answers.is_none() {
+        table.iter_mut().for_each(|v| _ = v.pop());
+    }
+
+    let mut widths = vec![0; table[0].len()];
+
+    for cells in &table {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = cell.chars().count().max(*width);
+        }
+    }
+
+    let mut output = String::new();
+
+    for cells in &table {
+        for (width, cell) in widths.iter().zip(cells) {
+            _ = write!(output, "{cell:<width$}  ");
+        }
+
+        output.truncate(output.trim_end().len());
+        output.push('\n');
+    }
+
+    _ = writeln!(output, "\n{} file(s), {failures} failed, {mismatches} mismatched", rows.len());
+
+    stdout().write_all(output.as_bytes())?;
+
+    if failures > 0 || mismatches > 0 {
+        bail!("{failures} file(s) failed and {mismatches} file(s) did not match their expected answers");
+    }
+
+    Ok(())
+}
+
+/// The results of running both parts against a single input file.
+#[derive(Clone, Debug)]
+struct Row {
+    /// The input file's name.
+    name: String,
+    /// The outcome and duration of each part, which has no duration if it was served from the cache.
+    parts: [(Outcome, Option<Duration>); 2],
+}
+
+impl Row {
+    /// Runs both parts of the given day against the input file at the given path.
+    fn solve(day: &Day, path: &Path, cache: Option<&Cache>) -> Self {
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |v| v.to_string_lossy().to_string());
+        let input = match inputs::read(path) {
+            Ok(input) => input,
+            Err(error) => {
+                let failure = (Outcome::Error(format!("{error:#}")), Some(Duration::ZERO));
+
+                return Self { name, parts: [failure.clone(), failure] };
+            }
+        };
+
+        let parts = [1, 2].map(|part| cache::solve(cache, day, part, &input));
+
+        Self { name, parts }
+    }
+
+    /// Compares this row against its expected answers, if any were given.
+    fn check(&self, expected: Option<&[Option<u64>; 2]>) -> Check {
+        let Some(expected) = expected else { return Check::Unknown };
+        let matches = self.parts.iter().zip(expected).all(|((outcome, _), expected)| match expected {
+            Some(expected) => *outcome == Outcome::Answer(*expected),
+            None => true,
+        });
+
+        if matches { Check::Match } else { Check::Mismatch }
+    }
+}
+
+/// How a row compares to its expected answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Check {
+    Match,
+    Mismatch,
+    Unknown,
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Match => "ok",
+            Self::Mismatch => "MISMATCH",
+            Self::Unknown => "no answers",
+        })
+    }
+}
+
+/// Returns every input file within the given directory in order, skipping the answers file if it happens to live there.
+fn list_inputs(directory: &Path, answers_path: Option<&Path>) -> Result<Vec<PathBuf>> {
+    let answers_path = answers_path.and_then(|v| v.canonicalize().ok());
+    let mut paths = Vec::new();
+
+    for entry in std::fs::read_dir(directory).with_context(|| format!("failed to read '{}'", directory.display()))? {
+        let path = entry?.path();
+
+        if path.is_file() && path.canonicalize().ok() != answers_path {
+            // Sealed files are unsealed while reading them, so they're listed under their original name.
+            paths.push(inputs::unsealed_path(&path).unwrap_or(path));
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+
+    Ok(paths)
+}
+
+/// Reads a sidecar answers file.
+///
+/// Every line contains a file name followed by its expected answers for both parts, separated by whitespace. Answers
+/// that aren't known yet can be written as `-`, and lines starting with `#` are ignored.
+///
+/// ```text
+/// # file       part 1  part 2
+/// alice.txt    143     123
+/// bob.txt      5091    -
+/// ```
+fn read_answers(path: &Path) -> Result<HashMap<String, [Option<u64>; 2]>> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("failed to read '{}'", path.display()))?;
+    let mut answers = HashMap::new();
+
+    for (index, line) in contents.lines().enumerate().filter(|(_, v)| !v.trim().is_empty() && !v.starts_with('#')) {
+        let (name, expected) = self::parse_answers(line)
+            .with_context(|| format!("invalid answers on line {} of '{}'", index + 1, path.display()))?;
+
+        answers.insert(name, expected);
+    }
+
+    Ok(answers)
+}
+
+/// Parses a single line of a sidecar answers file.
+fn parse_answers(line: &str) -> Result<(String, [Option<u64>; 2])> {
+    let mut columns = line.split_whitespace();
+    // The line is known to not be blank, so there's always at least one column.
+    let name = columns.next().unwrap_or_default().to_string();
+    let mut parse = || match columns.next() {
+        Some("-") => Ok(None),
+        Some(value) => value.parse().map(Some).with_context(|| format!("invalid answer '{value}'")),
+        None => bail!("missing answer"),
+    };
+
+    Ok((name, [parse()?, parse()?]))
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Read, Write, stdin, stdout};
+use std::panic::{set_hook, take_hook};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+
+use crate::arguments::Arguments;
+use crate::cache::{self, Cache};
+use crate::outcome::Outcome;
+use crate::{DAYS, Day};
+
+// Just enough ANSI escape codes to get by.
+const ENTER_SCREEN: &str = "\x1B[?1049h\x1B[?25l";
+const LEAVE_SCREEN: &str = "\x1B[?25h\x1B[?1049l";
+const CLEAR: &str = "\x1B[2J\x1B[H";
+const RESET: &str = "\x1B[0m";
+const BOLD: &str = "\x1B[1m";
+const DIM: &str = "\x1B[2m";
+const INVERT: &str = "\x1B[7m";
+const RED: &str = "\x1B[31m";
+const GREEN: &str = "\x1B[32m";
+const YELLOW: &str = "\x1B[33m";
+
+/// Opens an interactive terminal dashboard for browsing and running every registered day.
+///
+/// Usage: `dashboard [--cache | --no-cache]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let cache = Cache::from_arguments(&mut arguments)?;
+
+    arguments.finish()?;
+
+    let terminal = Terminal::enter()?;
+    let (sender, receiver) = mpsc::channel();
+    let (job_sender, job_receiver) = mpsc::channel::<Job>();
+
+    // Keys are read on their own thread, since reading from the terminal blocks until something is pressed.
+    let key_sender = sender.clone();
+
+    std::thread::spawn(move || {
+        let mut bytes = stdin().lock().bytes().map_while(Result::ok);
+
+        while let Some(key) = Key::read(&mut bytes) {
+            if key_sender.send(Event::Key(key)).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Solutions are run one at a time on a worker thread, which keeps the timings honest and the interface responsive.
+    std::thread::spawn(move || {
+        for job in job_receiver {
+            let (outcome, elapsed) = match job.day.read_input(job.example) {
+                Ok(input) => cache::solve(cache.as_ref(), job.day, job.part, &input),
+                Err(error) => (Outcome::Error(format!("{error:#}")), Some(Duration::ZERO)),
+            };
+
+            if sender.send(Event::Finished(job, outcome, elapsed)).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Panic messages would scribble all over the screen, so we silence them and show them in the detail pane instead.
+    let hook = take_hook();
+
+    set_hook(Box::new(|_| {}));
+
+    let mut state = State { selected_day: 0, selected_part: 1, example: true, results: HashMap::new() };
+    let result = (|| -> Result<()> {
+        loop {
+            terminal.draw(&state.render())?;
+
+            match receiver.recv()? {
+                Event::Key(Key::Quit) => return Ok(()),
+                Event::Key(Key::Up) => state.selected_day = state.selected_day.saturating_sub(1),
+                Event::Key(Key::Down) => state.selected_day = (state.selected_day + 1).min(DAYS.len() - 1),
+                Event::Key(Key::Left) => state.selected_part = 1,
+                Event::Key(Key::Right) => state.selected_part = 2,
+                Event::Key(Key::ToggleExample) => state.example = !state.example,
+                Event::Key(Key::Run) => {
+                    let job = Job { day: &DAYS[state.selected_day], part: state.selected_part, example: state.example };
+
+                    state.results.insert(job.key(), None);
+                    job_sender.send(job)?;
+                }
+                Event::Key(Key::RunAll) => {
+                    for day in DAYS {
+                        for part in [1, 2] {
+                            let job = Job { day, part, example: state.example };
+
+                            state.results.insert(job.key(), None);
+                            job_sender.send(job)?;
+                        }
+                    }
+                }
+                Event::Finished(job, outcome, elapsed) => _ = state.results.insert(job.key(), Some((outcome, elapsed))),
+            }
+        }
+    })();
+
+    set_hook(hook);
+    drop(terminal);
+
+    result
+}
+
+/// A solution that should be run by the worker thread.
+#[derive(Clone, Copy, Debug)]
+struct Job {
+    /// The day to run.
+    day: &'static Day,
+    /// The part to run.
+    part: u8,
+    /// Whether to use the example input.
+    example: bool,
+}
+
+impl Job {
+    /// Returns the key used to store this job's result.
+    const fn key(&self) -> (u8, u8, bool) {
+        (self.day.number, self.part, self.example)
+    }
+}
+
+/// The outcome of a finished job, along with how long it took if it wasn't served from the cache.
+type JobResult = (Outcome, Option<Duration>);
+
+/// Something that the dashboard needs to react to.
+#[derive(Debug)]
+enum Event {
+    Key(Key),
+    Finished(Job, Outcome, Option<Duration>),
+}
+
+/// A key press that the dashboard understands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    ToggleExample,
+    Run,
+    RunAll,
+    Quit,
+}
+
+impl Key {
+    /// Reads the next recognized key from the given bytes, skipping anything unrecognized.
+    ///
+    /// This returns `None` once the bytes run out.
+    fn read(bytes: &mut impl Iterator<Item = u8>) -> Option<Self> {
+        loop {
+            return Some(match bytes.next()? {
+                b'k' | b'w' => Self::Up,
+                b'j' | b's' => Self::Down,
+                b'h' | b'a' | b'1' => Self::Left,
+                b'l' | b'd' | b'2' => Self::Right,
+                b'e' => Self::ToggleExample,
+                b'\r' | b'\n' | b' ' | b'r' => Self::Run,
+                b'R' => Self::RunAll,
+                // This is either `q` or `Ctrl+C`, since the terminal won't send us signals while we're in control.
+                b'q' | 0x03 => Self::Quit,
+                // Arrow keys are sent as `ESC [ <letter>`.
+                0x1B if bytes.next()? == b'[' => match bytes.next()? {
+                    b'A' => Self::Up,
+                    b'B' => Self::Down,
+                    b'C' => Self::Right,
+                    b'D' => Self::Left,
+                    _ => continue,
+                },
+                _ => continue,
+            });
+        }
+    }
+}
+
+/// Everything that's currently shown on the dashboard.
+#[derive(Debug)]
+struct State {
+    /// The index of the selected day.
+    selected_day: usize,
+    /// The selected part.
+    selected_part: u8,
+    /// Whether the example inputs are used.
+    example: bool,
+    /// Every result so far, which are `None` while still running.
+    ///
+    /// Results served from the cache don't have a duration.
+    results: HashMap<(u8, u8, bool), Option<JobResult>>,
+}
+
+impl State {
+    /// Renders the dashboard into a string.
+    fn render(&self) -> String {
+        let mut output = String::from(CLEAR);
+        let data = if self.example { "example" } else { "real" };
+
+        _ = writeln!(output, "{BOLD}Advent of Code 2024{RESET} {DIM}-{RESET} using {BOLD}{data}{RESET} data\n");
+        _ = writeln!(output, "{BOLD}  Day  {:<32}{:<32}{RESET}", "Part 1", "Part 2");
+
+        for (index, day) in DAYS.iter().enumerate() {
+            let marker = if index == self.selected_day { '>' } else { ' ' };
+
+            _ = write!(output, "{marker} {:>3}  ", day.number);
+
+            for part in [1, 2] {
+                let (summary, style) = self.summary(day.number, part);
+                // Padding has to happen before styling, otherwise the escape codes would count towards the width.
+                let style = if index == self.selected_day && part == self.selected_part { INVERT } else { style };
+
+                _ = write!(output, "{style}{summary:<30}{RESET}  ");
+            }
+
+            output.push('\n');
+        }
+
+        let day = &DAYS[self.selected_day];
+
+        _ = writeln!(output, "\n{BOLD}Day {}, part {} ({data} data){RESET}", day.number, self.selected_part);
+        _ = writeln!(output, "{DIM}{}{RESET}", day.input_path(self.example).display());
+
+        match self.results.get(&(day.number, self.selected_part, self.example)) {
+            None => _ = writeln!(output, "Not run yet."),
+            Some(None) => _ = writeln!(output, "{YELLOW}Running...{RESET}"),
+            Some(Some((outcome, elapsed))) => {
+                match outcome {
+                    Outcome::Answer(answer) => _ = writeln!(output, "Answer: {GREEN}{BOLD}{answer}{RESET}"),
+                    Outcome::Error(error) => _ = writeln!(output, "{RED}Error:{RESET} {error}"),
+                    Outcome::Panic(message) => _ = writeln!(output, "{RED}Panic:{RESET} {message}"),
+                }
+
+                match elapsed {
+                    Some(elapsed) => _ = writeln!(output, "Time:   {elapsed:.2?}"),
+                    None => _ = writeln!(output, "Time:   {DIM}cached{RESET}"),
+                }
+            }
+        }
+
+        _ = write!(
+            output,
+            "\n{DIM}up/down: day  left/right: part  e: toggle example  enter: run  R: run all  q: quit{RESET}\n"
+        );
+
+        // Our terminal still translates newlines for us, but being explicit doesn't hurt.
+        output.replace('\n', "\r\n")
+    }
+
+    /// Returns a short summary of a result that fits inside the results table, along with its style.
+    fn summary(&self, day: u8, part: u8) -> (String, &'static str) {
+        match self.results.get(&(day, part, self.example)) {
+            None => ("-".to_string(), DIM),
+            Some(None) => ("running".to_string(), YELLOW),
+            Some(Some((Outcome::Answer(answer), Some(elapsed)))) => (format!("{answer} ({elapsed:.2?})"), GREEN),
+            Some(Some((Outcome::Answer(answer), None))) => (format!("{answer} (cached)"), GREEN),
+            Some(Some((Outcome::Error(_), _))) => ("error".to_string(), RED),
+            Some(Some((Outcome::Panic(_), _))) => ("panic".to_string(), RED),
+        }
+    }
+}
+
+/// Takes over the terminal until dropped.
+struct Terminal {
+    /// The terminal settings from before we took over, which are restored once dropped.
+    settings: String,
+}
+
+impl Terminal {
+    /// Switches the terminal into an alternate screen that receives key presses as they happen.
+    fn enter() -> Result<Self> {
+        // There's no way to do this with only the standard library, so we ask `stty` nicely instead.
+        let output = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output()?;
+
+        if !output.status.success() {
+            bail!("the dashboard must be run within an interactive terminal");
+        }
+
+        let settings = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        Command::new("stty").args(["-icanon", "-echo", "-isig", "min", "1"]).stdin(Stdio::inherit()).status()?;
+
+        let mut stdout = stdout().lock();
+
+        stdout.write_all(ENTER_SCREEN.as_bytes())?;
+        stdout.flush()?;
+
+        Ok(Self { settings })
+    }
+
+    /// Replaces the screen's contents.
+    fn draw(&self, contents: &str) -> Result<()> {
+        let mut stdout = stdout().lock();
+
+        stdout.write_all(contents.as_bytes())?;
+        stdout.flush().map_err(Into::into)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        _ = stdout().write_all(LEAVE_SCREEN.as_bytes());
+        _ = stdout().flush();
+        _ = Command::new("stty").arg(&self.settings).stdin(Stdio::inherit()).status();
+    }
+}
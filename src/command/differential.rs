@@ -0,0 +1,192 @@
+use std::fmt::Display;
+use std::panic::{set_hook, take_hook};
+
+use anyhow::{Result, bail};
+
+use crate::arguments::Arguments;
+use crate::outcome::Outcome;
+use crate::utility::random::Random;
+use crate::{Day, Solution};
+
+/// The number of inputs checked if none is specified.
+const DEFAULT_CASES: u64 = 200;
+/// The largest input size used if none is specified.
+const DEFAULT_SIZE: usize = 12;
+
+/// Runs a day's solutions and reference solutions against many generated inputs, reporting the first input where
+/// they disagree after shrinking it down as far as possible.
+///
+/// Usage: `differential <day #> [--part <1 | 2>] [--cases <N>] [--size <N>] [--seed <S>]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let part: Option<u8> = arguments.option("--part")?;
+    let cases = arguments.option("--cases")?.unwrap_or(DEFAULT_CASES);
+    let size = arguments.option("--size")?.unwrap_or(DEFAULT_SIZE).max(1);
+    let seed = arguments.option("--seed")?.unwrap_or(0);
+    let day = Day::find(arguments.next("day")?)?;
+
+    arguments.finish()?;
+
+    let mut disagreements = 0;
+
+    for part in part.map_or(vec![1, 2], |v| vec![v]) {
+        let solution = day.solution(part)?;
+        let Some(reference) = day.reference(part)? else {
+            println!("day {}, part {part}: no reference solution has been registered, skipping", day.number);
+
+            continue;
+        };
+
+        // Solutions are allowed to panic here, but we don't want hundreds of panic messages drowning out the report.
+        let hook = take_hook();
+
+        set_hook(Box::new(|_| {}));
+
+        let counterexample = self::find_counterexample(day, solution, reference, cases, size, seed);
+
+        set_hook(hook);
+
+        let Some(counterexample) = counterexample else {
+            println!("day {}, part {part}: all {cases} cases agree", day.number);
+
+            continue;
+        };
+
+        disagreements += 1;
+
+        println!("day {}, part {part}: {counterexample}", day.number);
+    }
+
+    if disagreements > 0 {
+        bail!("found {disagreements} disagreement(s) between solutions and their references");
+    }
+
+    Ok(())
+}
+
+/// An input where a solution and its reference disagree.
+#[derive(Clone, Debug)]
+struct Counterexample {
+    /// The seed used to generate the original input.
+    seed: u64,
+    /// The size used to generate the original input.
+    size: usize,
+    /// The shrunk input.
+    input: String,
+    /// The solution's outcome for the shrunk input.
+    solution: Outcome,
+    /// The reference's outcome for the shrunk input.
+    reference: Outcome,
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "solutions disagree (generated with --seed {} --size {})", self.seed, self.size)?;
+        writeln!(f, "  solution {}", self.solution)?;
+        writeln!(f, "  reference {}", self.reference)?;
+        writeln!(f, "shrunk input:")?;
+
+        self.input.lines().try_for_each(|line| writeln!(f, "  {line}"))
+    }
+}
+
+/// Generates inputs until the solution and reference disagree, returning the shrunk input.
+fn find_counterexample(
+    day: &Day,
+    solution: Solution,
+    reference: Solution,
+    cases: u64,
+    max_size: usize,
+    seed: u64,
+) -> Option<Counterexample> {
+    let disagrees = |input: &str| !Outcome::of(solution, input).agrees_with(&Outcome::of(reference, input));
+
+    // We cycle through sizes so that the smaller (and easier to read) inputs get checked first.
+    let (seed, size, input) = (0 .. cases).find_map(|case| {
+        let seed = seed.wrapping_add(case);
+        let size = 1 + (case as usize % max_size);
+        let input = (day.generate)(&mut Random::new(seed), size);
+
+        disagrees(&input).then_some((seed, size, input))
+    })?;
+
+    let input = self::shrink(&input, disagrees);
+
+    Some(Counterexample {
+        seed,
+        size,
+        solution: Outcome::of(solution, &input),
+        reference: Outcome::of(reference, &input),
+        input,
+    })
+}
+
+/// Removes as much of the input as possible while the solutions keep disagreeing.
+///
+/// This tries removing whole lines, then whole columns (so that grids stay rectangular), then characters within each
+/// line, and starts over until none of those make any progress.
+fn shrink(input: &str, disagrees: impl Fn(&str) -> bool) -> String {
+    let join = |lines: &[Vec<char>]| lines.iter().flat_map(|v| v.iter().copied().chain(['\n'])).collect::<String>();
+    let mut lines: Vec<Vec<char>> = input.lines().map(|v| v.chars().collect()).collect();
+
+    loop {
+        let previous = lines.clone();
+
+        self::remove_chunks(&mut lines, |lines| disagrees(&join(lines)));
+
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let mut columns: Vec<usize> = (0 .. width).collect();
+
+        self::remove_chunks(&mut columns, |columns| {
+            let lines: Vec<Vec<char>> = lines
+                .iter()
+                .map(|line| line.iter().enumerate().filter(|(i, _)| columns.contains(i)).map(|(_, c)| *c).collect())
+                .collect();
+
+            disagrees(&join(&lines))
+        });
+
+        lines.iter_mut().for_each(|line| *line = columns.iter().filter_map(|i| line.get(*i)).copied().collect());
+
+        for index in 0 .. lines.len() {
+            let mut line = lines[index].clone();
+
+            self::remove_chunks(&mut line, |line| {
+                let mut lines = lines.clone();
+
+                lines[index] = line.to_vec();
+
+                disagrees(&join(&lines))
+            });
+
+            lines[index] = line;
+        }
+
+        if lines == previous {
+            return join(&lines);
+        }
+    }
+}
+
+/// Removes as many chunks of the list as possible while the given predicate continues to hold, starting with large
+/// chunks and working down to single items.
+fn remove_chunks<T: Clone>(list: &mut Vec<T>, mut holds: impl FnMut(&[T]) -> bool) {
+    let mut chunk = list.len().div_ceil(2);
+
+    while chunk > 0 {
+        let mut start = 0;
+
+        while start < list.len() {
+            let mut candidate = list.clone();
+
+            candidate.drain(start .. (start + chunk).min(list.len()));
+
+            if holds(&candidate) {
+                *list = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+
+        chunk /= 2;
+    }
+}
@@ -0,0 +1,330 @@
+use std::panic::{catch_unwind, set_hook, take_hook};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+
+use crate::arguments::Arguments;
+use crate::outcome::panic_message;
+use crate::utility::hash::fnv1a_hex;
+use crate::utility::random::Random;
+use crate::{DAYS, Day};
+
+/// The number of mutated inputs checked per target if none is specified.
+const DEFAULT_CASES: u64 = 500;
+/// The longest a single case may run for, in seconds, if none is specified.
+const DEFAULT_TIMEOUT: u64 = 5;
+/// The directory that failing cases are saved to if none is specified.
+const DEFAULT_CORPUS: &str = "./target/fuzz-corpus";
+/// The directory of committed regression cases, which are always checked alongside the corpus.
+const REGRESSIONS: &str = "./data/fuzz";
+/// Bytes that are more likely to confuse a parser than whatever a random byte would be.
+const INTERESTING_BYTES: &[u8] = b"0123456789 \n\r\t:,|-+#.^()";
+/// Numbers that are too large for (or sit right at the edges of) the integer types used by the parsers.
+const HUGE_NUMBERS: &[&str] = &[
+    "0",
+    "65535",
+    "65536",
+    "4294967295",
+    "4294967296",
+    "18446744073709551615",
+    "18446744073709551616",
+    "340282366920938463463374607431768211456",
+];
+
+/// Feeds every day's parsers and solutions with mutated copies of its example input, checking that bad input is only
+/// ever reported as an error and never causes a panic.
+///
+/// Every failing case is saved to the corpus directory, and every case already within it (or within `./data/fuzz`) is
+/// checked again before anything new is generated, so fixed failures stay fixed.
+///
+/// Usage: `fuzz [<day #>...] [--cases <N>] [--seed <S>] [--timeout <seconds>] [--corpus <directory>]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let cases = arguments.option("--cases")?.unwrap_or(DEFAULT_CASES);
+    let seed = arguments.option("--seed")?.unwrap_or(0);
+    let timeout = Duration::from_secs(arguments.option("--timeout")?.unwrap_or(DEFAULT_TIMEOUT));
+    let corpus: PathBuf = arguments.option("--corpus")?.unwrap_or_else(|| PathBuf::from(DEFAULT_CORPUS));
+    let mut days = Vec::new();
+
+    while let Some(number) = arguments.next_optional("day")? {
+        days.push(Day::find(number)?);
+    }
+
+    arguments.finish()?;
+
+    if days.is_empty() {
+        days.extend(DAYS);
+    }
+
+    std::fs::create_dir_all(&corpus)
+        .with_context(|| format!("failed to create the corpus directory '{}'", corpus.display()))?;
+
+    // Panics are the entire point here, and hundreds of their messages would drown out the report.
+    let hook = take_hook();
+
+    set_hook(Box::new(|_| {}));
+
+    let result = (|| -> Result<usize> {
+        let mut failures = 0;
+
+        for day in &days {
+            failures += self::fuzz_day(day, cases, seed, timeout, &corpus)?;
+        }
+
+        Ok(failures)
+    })();
+
+    set_hook(hook);
+
+    let failures = result?;
+
+    if failures > 0 {
+        bail!("found {failures} input(s) that caused a panic or hang, saved within '{}'", corpus.display());
+    }
+
+    Ok(())
+}
+
+/// Something that an input can be fed to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Target {
+    /// One of the day's solutions.
+    Part(u8),
+    /// The day's REPL session, which has a parser of its own.
+    Repl,
+    /// The day's input identification.
+    Identify,
+}
+
+impl Target {
+    /// Returns every target that the given day has.
+    fn all(day: &Day) -> Vec<Self> {
+        let mut targets = vec![Self::Part(1), Self::Part(2), Self::Identify];
+
+        targets.extend(day.repl.map(|_| Self::Repl));
+        targets
+    }
+
+    /// Returns this target's name, which is also used to name its saved cases.
+    fn name(self) -> String {
+        match self {
+            Self::Part(part) => format!("part-{part}"),
+            Self::Repl => "repl".to_string(),
+            Self::Identify => "identify".to_string(),
+        }
+    }
+
+    /// Feeds the given input to this target on another thread, so that it can be abandoned if it never finishes.
+    fn check(self, day: &'static Day, input: String, timeout: Duration) -> Verdict {
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            // Whether the input was accepted or rejected doesn't matter, so long as it didn't panic.
+            let result = catch_unwind(|| match self {
+                Self::Part(part) => _ = day.solutions[part as usize - 1](&input),
+                Self::Repl => _ = day.repl.map(|open| open(&input)),
+                Self::Identify => _ = (day.identify)(&input),
+            });
+
+            _ = sender.send(result.map_err(|v| panic_message(v.as_ref())));
+        });
+
+        // A thread that never finishes just keeps running in the background, since there's no way to stop it.
+        match receiver.recv_timeout(timeout) {
+            Ok(Ok(())) => Verdict::Passed,
+            Ok(Err(message)) => Verdict::Panicked(message),
+            Err(_) => Verdict::TimedOut,
+        }
+    }
+}
+
+/// The result of feeding an input to a target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Verdict {
+    Passed,
+    Panicked(String),
+    TimedOut,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passed => f.write_str("passed"),
+            Self::Panicked(message) => write!(f, "panicked with '{message}'"),
+            Self::TimedOut => f.write_str("timed out"),
+        }
+    }
+}
+
+/// Fuzzes every target of the given day, returning the number of failures that were found.
+fn fuzz_day(day: &'static Day, cases: u64, seed: u64, timeout: Duration, corpus: &Path) -> Result<usize> {
+    let example = day.read_input(true)?;
+    let mut failures = 0;
+
+    for target in Target::all(day) {
+        let label = format!("day {}, {}", day.number, target.name().replace('-', " "));
+
+        // Unfinished solutions panic no matter what they're given, so there's nothing to learn from fuzzing them.
+        if let verdict @ (Verdict::Panicked(_) | Verdict::TimedOut) = target.check(day, example.clone(), timeout) {
+            println!("{label}: skipped, since the unmodified example already {verdict}");
+
+            continue;
+        }
+
+        // Saved cases are checked first, since they're the ones that are known to have failed before.
+        let mut saved = self::read_corpus(Path::new(REGRESSIONS), day, target)?;
+
+        saved.extend(self::read_corpus(corpus, day, target)?);
+
+        let generated = (0 .. cases).map(|case| self::mutate(&mut Random::new(seed.wrapping_add(case)), &example));
+        let failure =
+            saved.into_iter().chain(generated).find_map(|input| match target.check(day, input.clone(), timeout) {
+                Verdict::Passed => None,
+                verdict => Some((input, verdict)),
+            });
+
+        let Some((input, verdict)) = failure else {
+            println!("{label}: all cases passed");
+
+            continue;
+        };
+
+        let path = corpus.join(format!("day-{}-{}-{}.txt", day.number, target.name(), fnv1a_hex(input.as_bytes())));
+
+        std::fs::write(&path, &input).with_context(|| format!("failed to save '{}'", path.display()))?;
+
+        failures += 1;
+
+        println!("{label}: {verdict}, saved to '{}'", path.display());
+    }
+
+    Ok(failures)
+}
+
+/// Reads every saved case for the given day and target, if the directory exists at all.
+fn read_corpus(corpus: &Path, day: &Day, target: Target) -> Result<Vec<String>> {
+    let prefix = format!("day-{}-{}-", day.number, target.name());
+    let mut inputs = Vec::new();
+
+    if !corpus.is_dir() {
+        return Ok(inputs);
+    }
+
+    for entry in std::fs::read_dir(corpus).with_context(|| format!("failed to read '{}'", corpus.display()))? {
+        let path = entry?.path();
+
+        if path.file_name().is_some_and(|v| v.to_string_lossy().starts_with(&prefix)) {
+            inputs
+                .push(std::fs::read_to_string(&path).with_context(|| format!("failed to read '{}'", path.display()))?);
+        }
+    }
+
+    Ok(inputs)
+}
+
+/// Applies a handful of random mutations to the given input.
+///
+/// Inputs never grow past twice the size of the original, which keeps the slower solutions from turning every case
+/// into a timeout.
+fn mutate(random: &mut Random, input: &str) -> String {
+    let limit = input.len() * 2;
+    let mut bytes = input.as_bytes().to_vec();
+
+    for _ in 0 .. random.between(1, 4) {
+        let index = random.below(bytes.len() as u64 + 1) as usize;
+        let end = random.between(index as u64, bytes.len() as u64) as usize;
+
+        match random.below(7) {
+            // Flip a byte, usually into one that means something to a parser.
+            0 if index < bytes.len() => {
+                bytes[index] = match random.chance(3, 4) {
+                    true => INTERESTING_BYTES[random.below(INTERESTING_BYTES.len() as u64) as usize],
+                    false => random.below(256) as u8,
+                };
+            }
+            // Cut the input short.
+            1 => bytes.truncate(index),
+            // Repeat a chunk of the input somewhere else.
+            2 => {
+                let chunk = bytes[index .. end.min(index + limit.saturating_sub(bytes.len()))].to_vec();
+                let at = random.below(bytes.len() as u64 + 1) as usize;
+
+                bytes.splice(at .. at, chunk);
+            }
+            // Swap a number out for one that's far too large.
+            3 => {
+                let Some(start) = bytes[index ..].iter().position(u8::is_ascii_digit).map(|v| v + index) else {
+                    continue;
+                };
+                let length = bytes[start ..].iter().take_while(|v| v.is_ascii_digit()).count();
+                let number = HUGE_NUMBERS[random.below(HUGE_NUMBERS.len() as u64) as usize];
+
+                bytes.splice(start .. start + length, number.bytes());
+            }
+            // Add a copy of a line with its numbers reversed, which turns rules like `a|b` into contradictions.
+            4 => {
+                let (start, end) = self::line_around(&bytes, index);
+                let line = self::reverse_numbers(&bytes[start .. end]);
+
+                if bytes.len() + line.len() < limit {
+                    bytes.splice(
+                        end .. end,
+                        [
+                            b"
+"
+                            .as_slice(),
+                            &line,
+                        ]
+                        .concat(),
+                    );
+                }
+            }
+            // Join a few lines onto each other, which makes lists far longer than any the example has.
+            5 => {
+                let (start, mut end) = self::line_around(&bytes, index);
+                let separator = bytes[start .. end].iter().copied().find(|v| v.is_ascii_punctuation()).unwrap_or(b' ');
+
+                for _ in 0 .. random.between(1, 8) {
+                    // Blank lines usually separate sections, so joining past one would just make a mess.
+                    if end + 1 >= bytes.len() || bytes[end + 1] == b'\n' {
+                        break;
+                    }
+
+                    bytes[end] = separator;
+                    end = self::line_around(&bytes, end + 1).1;
+                }
+            }
+            // Remove a chunk of the input.
+            _ => _ = bytes.drain(index .. end),
+        }
+    }
+
+    // Random bytes aren't always valid UTF-8, but the solutions only ever see strings.
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Returns where the line containing the given index starts and ends, not counting its line break.
+fn line_around(bytes: &[u8], index: usize) -> (usize, usize) {
+    let index = index.min(bytes.len());
+    let start = bytes[.. index].iter().rposition(|v| *v == b'\n').map_or(0, |v| v + 1);
+    let end = bytes[index ..].iter().position(|v| *v == b'\n').map_or(bytes.len(), |v| v + index);
+
+    (start, end)
+}
+
+/// Returns a copy of the given line with the order of its numbers reversed, leaving everything between them alone.
+fn reverse_numbers(line: &[u8]) -> Vec<u8> {
+    let numbers: Vec<&[u8]> = line.split(|v| !v.is_ascii_digit()).filter(|v| !v.is_empty()).collect();
+    let mut numbers = numbers.into_iter().rev();
+    let mut output = Vec::with_capacity(line.len());
+
+    for chunk in line.chunk_by(|a, b| a.is_ascii_digit() == b.is_ascii_digit()) {
+        match chunk[0].is_ascii_digit() {
+            true => output.extend_from_slice(numbers.next().unwrap_or_default()),
+            false => output.extend_from_slice(chunk),
+        }
+    }
+
+    output
+}
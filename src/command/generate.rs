@@ -0,0 +1,25 @@
+use std::io::{Write, stdout};
+
+use anyhow::Result;
+
+use crate::Day;
+use crate::arguments::Arguments;
+use crate::utility::random::Random;
+
+/// The size used if none is specified.
+const DEFAULT_SIZE: usize = 100;
+
+/// Generates a random input for a day and writes it to the standard output.
+///
+/// Usage: `generate <day #> [--size <N>] [--seed <S>]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let size = arguments.option("--size")?.unwrap_or(DEFAULT_SIZE);
+    let seed = arguments.option("--seed")?.unwrap_or(0);
+    let day = Day::find(arguments.next("day")?)?;
+
+    arguments.finish()?;
+
+    let input = (day.generate)(&mut Random::new(seed), size);
+
+    stdout().write_all(input.as_bytes()).map_err(Into::into)
+}
@@ -0,0 +1,27 @@
+use std::fmt::Write as _;
+use std::io::{Write, stdout};
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::arguments::Arguments;
+use crate::{identify, inputs};
+
+/// Guesses which day an input file belongs to, printing every day from most to least likely.
+///
+/// Usage: `identify <file>`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let path: PathBuf = arguments.next("file")?;
+
+    arguments.finish()?;
+
+    // Sealed inputs are unsealed just like they are for `run`, so they can be identified too.
+    let input = inputs::read(&path)?;
+    let mut output = String::new();
+
+    for (day, confidence) in identify::rank(&input) {
+        _ = writeln!(output, "day {:>2}  {:>5.1}%", day.number, confidence * 100.0);
+    }
+
+    stdout().write_all(output.as_bytes()).map_err(Into::into)
+}
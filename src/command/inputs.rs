@@ -0,0 +1,116 @@
+use anyhow::{Context, Result, bail};
+
+use crate::arguments::Arguments;
+use crate::inputs::{self, KEY_VARIABLE};
+use crate::utility::cipher::Key;
+use crate::{DAYS, Day};
+
+/// Manages sealed copies of the real inputs, which are encrypted so that they can be committed without publishing them.
+///
+/// - `inputs key` prints a new random key, to be stored in the environment variable named by [`KEY_VARIABLE`].
+/// - `inputs seal [<day #>...]` encrypts every real input that's present.
+/// - `inputs unseal [<day #>...] [--force]` decrypts every sealed input that's present.
+///
+/// Example inputs are already public, so they're never sealed.
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let force = arguments.flag("--force");
+    let action: String = arguments.next("action")?;
+    let mut days = Vec::new();
+
+    while let Some(day) = arguments.next_optional("day")? {
+        days.push(Day::find(day)?);
+    }
+
+    arguments.finish()?;
+
+    // No days means every day.
+    let days: Vec<&Day> = if days.is_empty() { DAYS.iter().collect() } else { days };
+
+    match action.as_str() {
+        "key" => {
+            let key = inputs::generate_key()?;
+
+            println!("{}", key.iter().map(|v| format!("{v:02x}")).collect::<String>());
+            eprintln!("store this somewhere safe, then set it as {KEY_VARIABLE} to seal or unseal inputs");
+
+            Ok(())
+        }
+        "seal" => self::seal(&self::require_key()?, &days),
+        "unseal" => self::unseal(&self::require_key()?, &days, force),
+        action => bail!("unknown action '{action}', expected 'key', 'seal', or 'unseal'"),
+    }
+}
+
+/// Returns the key contained within the environment, failing if it hasn't been set.
+fn require_key() -> Result<Key> {
+    inputs::key()?.with_context(|| format!("{KEY_VARIABLE} must be set, try running `inputs key` to create a key"))
+}
+
+/// Seals the real input of every given day, if it's present.
+fn seal(key: &Key, days: &[&Day]) -> Result<()> {
+    for day in days {
+        let path = day.input_path(false);
+        let sealed_path = inputs::sealed_path(&path);
+
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            println!("day {}: skipped, '{}' does not exist", day.number, path.display());
+
+            continue;
+        };
+
+        // Every seal uses a fresh nonce, so re-sealing an unchanged input would produce a pointless diff.
+        let unchanged = std::fs::read(&sealed_path)
+            .ok()
+            .and_then(|v| inputs::unseal(key, &path, &v).ok())
+            .is_some_and(|v| v == contents);
+
+        if unchanged {
+            println!("day {}: unchanged", day.number);
+
+            continue;
+        }
+
+        std::fs::write(&sealed_path, inputs::seal(key, &path, &contents)?)?;
+
+        println!("day {}: sealed into '{}'", day.number, sealed_path.display());
+    }
+
+    Ok(())
+}
+
+/// Unseals the real input of every given day, if a sealed copy is present.
+///
+/// Existing inputs are only ever overwritten if `force` is `true`.
+fn unseal(key: &Key, days: &[&Day], force: bool) -> Result<()> {
+    for day in days {
+        let path = day.input_path(false);
+        let sealed_path = inputs::sealed_path(&path);
+
+        let Ok(sealed) = std::fs::read(&sealed_path) else {
+            println!("day {}: skipped, '{}' does not exist", day.number, sealed_path.display());
+
+            continue;
+        };
+
+        let contents = inputs::unseal(key, &path, &sealed)
+            .with_context(|| format!("failed to unseal '{}'", sealed_path.display()))?;
+
+        match std::fs::read_to_string(&path) {
+            Ok(existing) if existing == contents => println!("day {}: unchanged", day.number),
+            Ok(_) if !force => {
+                println!(
+                    "day {}: skipped, '{}' differs from its sealed copy (use --force)",
+                    day.number,
+                    path.display()
+                );
+            }
+            _ => {
+                std::fs::write(&path, contents)?;
+
+                println!("day {}: unsealed into '{}'", day.number, path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
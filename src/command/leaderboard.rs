@@ -0,0 +1,578 @@
+use std::fmt::Write as _;
+use std::io::{Read, Write, stdout};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+
+use crate::arguments::Arguments;
+use crate::utility::json::Json;
+
+/// The sections that are printed if `--show` isn't given, in order.
+const DEFAULT_SECTIONS: &[Section] = &[Section::Ranking, Section::Calendar, Section::Times, Section::Deltas];
+/// The number of days within an event.
+const EVENT_DAYS: usize = 25;
+/// The number of seconds within a day.
+const DAY_SECONDS: i64 = 24 * 60 * 60;
+/// How long we wait on a leaderboard server before giving up on it.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// The last year we accept, which keeps every timestamp small enough to subtract from another without overflowing.
+const MAX_YEAR: i64 = 9999;
+/// The last timestamp we accept, which is the end of [`MAX_YEAR`].
+const MAX_TIMESTAMP: i64 = self::days_from_civil(MAX_YEAR + 1, 1, 1) * DAY_SECONDS - 1;
+
+/// Prints statistics about a private leaderboard, read from the JSON that Advent of Code exports for it.
+///
+/// The JSON can either be read from a file, or fetched from a plain HTTP URL (like a local stub serving a copy).
+///
+/// Usage: `leaderboard <file.json | --url <url>> [--show <section,...>] [--sort <local | stars | name>] [--top <N>]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let url: Option<String> = arguments.option("--url")?;
+    let sections = match arguments.option::<String>("--show")? {
+        Some(sections) => sections.split(',').map(Section::parse).collect::<Result<Vec<_>>>()?,
+        None => DEFAULT_SECTIONS.to_vec(),
+    };
+    let order = match arguments.option::<String>("--sort")? {
+        Some(order) => Order::parse(&order)?,
+        None => Order::Local,
+    };
+    let top: Option<usize> = arguments.option("--top")?;
+    let path: Option<PathBuf> = if url.is_none() { Some(arguments.next("leaderboard file")?) } else { None };
+
+    arguments.finish()?;
+
+    let source = match (&url, &path) {
+        (Some(url), _) => self::fetch(url).with_context(|| format!("failed to fetch '{url}'"))?,
+        (None, Some(path)) => {
+            std::fs::read_to_string(path).with_context(|| format!("failed to read '{}'", path.display()))?
+        }
+        // Either the URL or the path is always present, since the path is required without a URL.
+        (None, None) => unreachable!(),
+    };
+
+    let json = Json::parse(&source).context("failed to parse the leaderboard")?;
+    let mut leaderboard = Leaderboard::from_json(&json).context("the leaderboard is not in the expected format")?;
+
+    leaderboard.sort(order);
+    leaderboard.members.truncate(top.unwrap_or(usize::MAX));
+
+    let mut output = format!("event {}, {} member(s)\n", leaderboard.event, leaderboard.members.len());
+
+    for section in sections {
+        output.push('\n');
+
+        match section {
+            Section::Ranking => leaderboard.write_ranking(&mut output),
+            Section::Calendar => leaderboard.write_calendar(&mut output),
+            Section::Times => leaderboard.write_times(&mut output),
+            Section::Deltas => leaderboard.write_deltas(&mut output),
+        }
+    }
+
+    stdout().write_all(output.as_bytes())?;
+
+    Ok(())
+}
+
+/// A section of the printed output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Section {
+    /// Every member, in order.
+    Ranking,
+    /// A grid of every member's stars.
+    Calendar,
+    /// How long every member took to get each star.
+    Times,
+    /// How long every member took to get from the first star to the second.
+    Deltas,
+}
+
+impl Section {
+    /// Parses a section from its name.
+    fn parse(name: &str) -> Result<Self> {
+        Ok(match name.trim() {
+            "ranking" => Self::Ranking,
+            "calendar" => Self::Calendar,
+            "times" => Self::Times,
+            "deltas" => Self::Deltas,
+            name => bail!("unknown section '{name}', expected one of ranking, calendar, times, or deltas"),
+        })
+    }
+}
+
+/// How members are ordered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Order {
+    /// By local score, which is what the website does by default.
+    Local,
+    /// By number of stars, with ties going to whoever got there first.
+    Stars,
+    /// Alphabetically, for when it's not a competition.
+    Name,
+}
+
+impl Order {
+    /// Parses an order from its name.
+    fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "local" => Self::Local,
+            "stars" => Self::Stars,
+            "name" => Self::Name,
+            name => bail!("unknown order '{name}', expected one of local, stars, or name"),
+        })
+    }
+}
+
+/// A private leaderboard.
+#[derive(Clone, Debug)]
+struct Leaderboard {
+    /// The event's year.
+    event: String,
+    /// When the first day's puzzle was unlocked, as a Unix timestamp.
+    first_unlock: i64,
+    /// Every member of the leaderboard.
+    members: Vec<Member>,
+}
+
+/// A member of a private leaderboard.
+#[derive(Clone, Debug)]
+struct Member {
+    /// The member's name, or a placeholder for anonymous users.
+    name: String,
+    /// The member's local score.
+    local_score: i128,
+    /// The member's total number of stars.
+    stars: i128,
+    /// When the member last got a star, as a Unix timestamp.
+    last_star: i64,
+    /// When the member got each star of each day, as Unix timestamps.
+    completions: [[Option<i64>; 2]; EVENT_DAYS],
+}
+
+impl Leaderboard {
+    /// Reads a leaderboard from its exported JSON.
+    fn from_json(json: &Json) -> Result<Self> {
+        let event = match json.get("event") {
+            Some(Json::String(event)) => event.clone(),
+            Some(Json::Integer(event)) => event.to_string(),
+            _ => bail!("missing 'event'"),
+        };
+        // Older exports don't include this, but it's always midnight on the first of December in New York (UTC-5).
+        let first_unlock = match json.get("day1_ts").map(self::timestamp).transpose().context("invalid 'day1_ts'")? {
+            Some(timestamp) => timestamp,
+            None => {
+                let Some(year) = event.parse().ok().filter(|v| (1970 ..= MAX_YEAR).contains(v)) else {
+                    bail!("invalid event '{event}'");
+                };
+
+                self::days_from_civil(year, 12, 1) * DAY_SECONDS + 5 * 60 * 60
+            }
+        };
+        let entries = json.get("members").and_then(Json::as_object).context("missing 'members'")?;
+        let members = entries
+            .iter()
+            .map(|(id, member)| Member::from_json(id, member).with_context(|| format!("invalid member '{id}'")))
+            .collect::<Result<_>>()?;
+
+        Ok(Self { event, first_unlock, members })
+    }
+
+    /// Sorts every member using the given order.
+    fn sort(&mut self, order: Order) {
+        self.members.sort_by(|a, b| match order {
+            Order::Local => {
+                b.local_score.cmp(&a.local_score).then(b.stars.cmp(&a.stars)).then(a.last_star.cmp(&b.last_star))
+            }
+            Order::Stars => b.stars.cmp(&a.stars).then(a.last_star.cmp(&b.last_star)),
+            Order::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        });
+    }
+
+    /// Returns the number of days that anybody has a star for, which is as far as any section needs to go.
+    fn days(&self) -> usize {
+        self.members
+            .iter()
+            .filter_map(|v| v.completions.iter().rposition(|v| v[0].is_some()))
+            .max()
+            .map_or(0, |v| v + 1)
+    }
+
+    /// Returns how long after the given day (counting from 0) unlocked that the given timestamp happened.
+    const fn since_unlock(&self, day: usize, timestamp: i64) -> i64 {
+        timestamp - (self.first_unlock + day as i64 * DAY_SECONDS)
+    }
+
+    /// Writes every member, in order.
+    fn write_ranking(&self, output: &mut String) {
+        let mut table = vec![["#", "score", "stars", "name"].map(str::to_string).to_vec()];
+
+        for (index, member) in self.members.iter().enumerate() {
+            table.push(vec![
+                (index + 1).to_string(),
+                member.local_score.to_string(),
+                member.stars.to_string(),
+                member.name.clone(),
+            ]);
+        }
+
+        self::write_table(output, &table);
+    }
+
+    /// Writes a grid showing which stars every member has.
+    fn write_calendar(&self, output: &mut String) {
+        let width = self.members.iter().map(|v| v.name.chars().count()).max().unwrap_or(0);
+        let days = self.days().max(1);
+
+        // Day numbers are written vertically, since that's the only way 25 of them fit above 25 cells.
+        let tens: String =
+            (1 ..= days).map(|v| if v >= 10 { char::from(b'0' + (v / 10) as u8) } else { ' ' }).collect();
+        let ones: String = (1 ..= days).map(|v| char::from(b'0' + (v % 10) as u8)).collect();
+
+        if days >= 10 {
+            _ = writeln!(output, "{:width$}  {tens}", "");
+        }
+
+        _ = writeln!(output, "{:width$}  {ones}", "");
+
+        for member in &self.members {
+            let stars: String = member.completions[.. days]
+                .iter()
+                .map(|v| match v {
+                    [Some(_), Some(_)] => '*',
+                    [Some(_), None] => '+',
+                    _ => '.',
+                })
+                .collect();
+
+            _ = writeln!(output, "{:width$}  {stars}", member.name);
+        }
+
+        _ = writeln!(output, "\n* both stars, + first star only, . no stars");
+    }
+
+    /// Writes how long every member took to get each star of each day, in the order they finished.
+    fn write_times(&self, output: &mut String) {
+        let mut separate = false;
+
+        for day in 0 .. self.days() {
+            let mut members: Vec<_> = self.members.iter().filter(|v| v.completions[day][0].is_some()).collect();
+
+            if members.is_empty() {
+                continue;
+            }
+
+            // Anybody who's finished the whole day comes first, then everybody who's still on the second part.
+            members.sort_by_key(|v| (v.completions[day][1].is_none(), v.completions[day][1], v.completions[day][0]));
+
+            let mut table = vec![["name", "part 1", "part 2", "delta"].map(str::to_string).to_vec()];
+
+            for member in members {
+                let [first, second] = member.completions[day];
+                let format = |timestamp: Option<i64>| {
+                    timestamp.map_or_else(|| "-".to_string(), |v| self::format_duration(self.since_unlock(day, v)))
+                };
+                let delta = first.zip(second).map(|(first, second)| self::format_duration(second - first));
+
+                table.push(vec![
+                    member.name.clone(),
+                    format(first),
+                    format(second),
+                    delta.unwrap_or_else(|| "-".into()),
+                ]);
+            }
+
+            // Every day after the first is separated from the one before it.
+            if std::mem::replace(&mut separate, true) {
+                output.push('\n');
+            }
+
+            _ = writeln!(output, "day {}", day + 1);
+            self::write_table(output, &table);
+        }
+    }
+
+    /// Writes how long every member took between getting the first and second stars of each day.
+    fn write_deltas(&self, output: &mut String) {
+        let mut table = vec![["name", "days", "fastest", "median", "slowest"].map(str::to_string).to_vec()];
+
+        for member in &self.members {
+            let mut deltas: Vec<i64> = member
+                .completions
+                .iter()
+                .filter_map(|[first, second]| Some(second.as_ref()? - first.as_ref()?))
+                .collect();
+
+            deltas.sort_unstable();
+
+            let cell = |delta: Option<&i64>| delta.map_or_else(|| "-".to_string(), |v| self::format_duration(*v));
+
+            table.push(vec![
+                member.name.clone(),
+                deltas.len().to_string(),
+                cell(deltas.first()),
+                cell(deltas.get(deltas.len() / 2)),
+                cell(deltas.last()),
+            ]);
+        }
+
+        self::write_table(output, &table);
+    }
+}
+
+impl Member {
+    /// Reads a member from its exported JSON.
+    fn from_json(id: &str, json: &Json) -> Result<Self> {
+        let name = match json.get("name").and_then(Json::as_str) {
+            Some(name) => name.to_string(),
+            // This is what the website shows for anybody who hasn't made their name public.
+            None => format!("(anonymous user #{id})"),
+        };
+        let local_score = json.get("local_score").and_then(Json::as_integer).context("missing 'local_score'")?;
+        let stars = json.get("stars").and_then(Json::as_integer).context("missing 'stars'")?;
+        let last_star = json.get("last_star_ts").map(self::timestamp).transpose().context("invalid 'last_star_ts'")?;
+        let mut completions = [[None; 2]; EVENT_DAYS];
+
+        for (day, parts) in json.get("completion_day_level").and_then(Json::as_object).unwrap_or_default() {
+            let Some(index) = day.parse::<usize>().ok().filter(|v| (1 ..= EVENT_DAYS).contains(v)) else {
+                bail!("invalid day '{day}'");
+            };
+
+            for (part, completion) in completions[index - 1].iter_mut().enumerate() {
+                let timestamp = parts.get(&(part + 1).to_string()).and_then(|v| v.get("get_star_ts"));
+
+                *completion = timestamp
+                    .map(self::timestamp)
+                    .transpose()
+                    .with_context(|| format!("invalid star for part {} of day {day}", part + 1))?;
+            }
+        }
+
+        Ok(Self { name, local_score, stars, last_star: last_star.unwrap_or(0), completions })
+    }
+}
+
+/// Reads a Unix timestamp, which older exports wrote as strings.
+///
+/// Anything before the Unix epoch or after [`MAX_TIMESTAMP`] is rejected, so that the sections can do their maths on
+/// timestamps without worrying about overflow.
+fn timestamp(json: &Json) -> Result<i64> {
+    let timestamp = match json {
+        Json::Integer(value) => i64::try_from(*value).ok(),
+        Json::String(value) => value.parse().ok(),
+        _ => bail!("expected a timestamp"),
+    };
+
+    match timestamp.filter(|v| (0 ..= MAX_TIMESTAMP).contains(v)) {
+        Some(timestamp) => Ok(timestamp),
+        None => bail!("timestamp {json} is out of range"),
+    }
+}
+
+/// Returns the number of days between the Unix epoch and the given date.
+///
+/// This is Howard Hinnant's `days_from_civil`, which is about as short as calendar maths gets.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Formats a number of seconds as hours, minutes, and seconds.
+fn format_duration(seconds: i64) -> String {
+    // Clocks aren't always perfect, so anything negative is treated as instant.
+    let seconds = seconds.max(0);
+
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// Writes a table with left-aligned columns.
+fn write_table(output: &mut String, table: &[Vec<String>]) {
+    let mut widths = vec![0; table.first().map_or(0, Vec::len)];
+
+    for cells in table {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = cell.chars().count().max(*width);
+        }
+    }
+
+    for cells in table {
+        let start = output.len();
+
+        for (width, cell) in widths.iter().zip(cells) {
+            _ = write!(output, "{cell:<width$}  ");
+        }
+
+        output.truncate(start + output[start ..].trim_end().len());
+        output.push('\n');
+    }
+}
+
+/// Fetches the body of the given URL using a bare-bones HTTP/1.0 request.
+///
+/// Only plain `http://` is supported, which is plenty for a stub running locally. The real website needs HTTPS, and
+/// implementing TLS is where even this repository draws the line.
+fn fetch(url: &str) -> Result<String> {
+    let Some(rest) = url.strip_prefix("http://") else { bail!("only plain 'http://' URLs are supported") };
+    let (host, path) = rest.find('/').map_or((rest, "/"), |v| rest.split_at(v));
+    let address = if host.contains(':') { host.to_string() } else { format!("{host}:80") };
+    let address = address.to_socket_addrs()?.next().with_context(|| format!("failed to resolve '{host}'"))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, FETCH_TIMEOUT)?;
+
+    stream.set_read_timeout(Some(FETCH_TIMEOUT))?;
+    stream.set_write_timeout(Some(FETCH_TIMEOUT))?;
+
+    // HTTP/1.0 means that the server closes the connection when it's done, and can't send the body in chunks.
+    write!(stream, "GET {path} HTTP/1.0\r\nHost: {host}\r\nAccept: application/json\r\nConnection: close\r\n\r\n")?;
+
+    let mut response = Vec::new();
+
+    stream.read_to_end(&mut response)?;
+
+    let response = String::from_utf8(response).context("the response is not valid UTF-8")?;
+    let (head, body) = response.split_once("\r\n\r\n").context("the response is malformed")?;
+    let status = head.lines().next().unwrap_or_default();
+
+    if status.split_whitespace().nth(1) != Some("200") {
+        bail!("the server responded with '{status}'");
+    }
+
+    Ok(body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small leaderboard, without `day1_ts` so that the first unlock has to be worked out from the event.
+    const FIXTURE: &str = r#"{
+        "event": "2024",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "name": "Alice",
+                "local_score": 5,
+                "stars": 3,
+                "last_star_ts": 1733119200,
+                "completion_day_level": {
+                    "1": {"1": {"get_star_ts": 1733029800}, "2": {"get_star_ts": 1733030100}},
+                    "2": {"1": {"get_star_ts": 1733119200}}
+                }
+            },
+            "7": {
+                "name": null,
+                "local_score": 1,
+                "stars": 1,
+                "last_star_ts": "1733033000",
+                "completion_day_level": {"1": {"1": {"get_star_ts": "1733033000"}}}
+            }
+        }
+    }"#;
+
+    /// Parses the given leaderboard JSON.
+    fn leaderboard(source: &str) -> Result<Leaderboard> {
+        Leaderboard::from_json(&Json::parse(source)?)
+    }
+
+    /// Renders the fixture's given section.
+    fn render(write: fn(&Leaderboard, &mut String)) -> String {
+        let mut leaderboard = self::leaderboard(FIXTURE).unwrap();
+        let mut output = String::new();
+
+        leaderboard.sort(Order::Local);
+        write(&leaderboard, &mut output);
+
+        output
+    }
+
+    #[test]
+    fn first_unlock_comes_from_the_event() {
+        let leaderboard = self::leaderboard(FIXTURE).unwrap();
+
+        assert_eq!(self::days_from_civil(2024, 12, 1), 20_058);
+        assert_eq!(self::days_from_civil(1970, 1, 1), 0);
+        assert_eq!(self::days_from_civil(2000, 3, 1), 11_017);
+        // Midnight on the first of December in New York, which is 5 AM in UTC.
+        assert_eq!(leaderboard.first_unlock, 1_733_029_200);
+        assert_eq!(leaderboard.days(), 2);
+    }
+
+    #[test]
+    fn sections_render() {
+        // Every row is trimmed, so shorter names don't leave trailing spaces behind.
+        assert_eq!(
+            self::render(Leaderboard::write_ranking),
+            concat!("#  score  stars  name\n", "1  5      3      Alice\n", "2  1      1      (anonymous user #7)\n",)
+        );
+        assert_eq!(
+            self::render(Leaderboard::write_calendar),
+            concat!(
+                "                     12\n",
+                "Alice                *+\n",
+                "(anonymous user #7)  +.\n",
+                "\n",
+                "* both stars, + first star only, . no stars\n",
+            )
+        );
+        assert_eq!(
+            self::render(Leaderboard::write_times),
+            concat!(
+                "day 1\n",
+                "name                 part 1   part 2   delta\n",
+                "Alice                0:10:00  0:15:00  0:05:00\n",
+                "(anonymous user #7)  1:03:20  -        -\n",
+                "\n",
+                "day 2\n",
+                "name   part 1   part 2  delta\n",
+                "Alice  1:00:00  -       -\n",
+            )
+        );
+        assert_eq!(
+            self::render(Leaderboard::write_deltas),
+            concat!(
+                "name                 days  fastest  median   slowest\n",
+                "Alice                1     0:05:00  0:05:00  0:05:00\n",
+                "(anonymous user #7)  0     -        -        -\n",
+            )
+        );
+    }
+
+    #[test]
+    fn timestamps_are_bounded() {
+        let star = |timestamp: &str| {
+            let member = format!(
+                r#"{{"local_score": 0, "stars": 1, "completion_day_level": {{"1": {{"1": {{"get_star_ts": {timestamp}}}}}}}}}"#
+            );
+
+            self::leaderboard(&format!(r#"{{"event": 2024, "members": {{"1": {member}}}}}"#))
+        };
+
+        assert!(star("0").is_ok());
+        assert!(star(&MAX_TIMESTAMP.to_string()).is_ok());
+        assert!(star(&format!("\"{MAX_TIMESTAMP}\"")).is_ok());
+        assert!(star(&(MAX_TIMESTAMP + 1).to_string()).is_err());
+        assert!(star("-1").is_err());
+        assert!(star("-9223372036854775808").is_err());
+        assert!(star("\"soon\"").is_err());
+        assert!(star("1.5").is_err());
+    }
+
+    #[test]
+    fn events_are_bounded() {
+        let event = |event: &str| self::leaderboard(&format!(r#"{{"event": {event}, "members": {{}}}}"#));
+
+        assert_eq!(event("2015").unwrap().first_unlock, 1_448_946_000);
+        assert_eq!(event("\"2015\"").unwrap().first_unlock, 1_448_946_000);
+        assert!(event("\"9999\"").is_ok());
+        assert!(event("\"10000\"").is_err());
+        assert!(event("\"1969\"").is_err());
+        assert!(event("\"99999999999999999\"").is_err());
+        assert!(event("\"next year\"").is_err());
+        assert!(event("null").is_err());
+    }
+}
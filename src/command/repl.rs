@@ -0,0 +1,135 @@
+use std::io::{BufRead, Write, stdin, stdout};
+use std::panic::{AssertUnwindSafe, catch_unwind, set_hook, take_hook};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::{Result, bail};
+
+use crate::arguments::Arguments;
+use crate::outcome::{Outcome, panic_message};
+use crate::repl::Session;
+use crate::{Day, inputs};
+
+/// The commands that are available for every day.
+const GENERIC_COMMANDS: &[(&str, &str)] = &[
+    ("part <1 | 2>", "runs a solution against the input"),
+    ("reload", "reads and parses the input again"),
+    ("help", "lists every command"),
+    ("quit", "exits the REPL"),
+];
+
+/// Parses a day's input once, then reads commands for exploring it until told to stop.
+///
+/// Usage: `repl <day #> [<use example data? (0 or 1)>] [--input <path>]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let input_override: Option<PathBuf> = arguments.option("--input")?;
+    let day = Day::find(arguments.next("day")?)?;
+    let use_examples = arguments.next_optional::<u8>("example flag")?.is_some_and(|v| v == 1);
+
+    arguments.finish()?;
+
+    let path = input_override.unwrap_or_else(|| day.input_path(use_examples));
+    let mut repl = Repl { day, path, input: String::new(), session: None };
+
+    repl.reload()?;
+
+    // Panics are reported as the output of whatever command caused them, so the default messages would just be noise.
+    let hook = take_hook();
+
+    set_hook(Box::new(|_| {}));
+
+    let result = repl.read_commands();
+
+    set_hook(hook);
+
+    result
+}
+
+/// Everything the REPL keeps in memory between commands.
+struct Repl {
+    /// The day being explored.
+    day: &'static Day,
+    /// The path of the input file.
+    path: PathBuf,
+    /// The raw input.
+    input: String,
+    /// The parsed input, if the day has any commands of its own.
+    session: Option<Box<dyn Session>>,
+}
+
+impl Repl {
+    /// Reads and parses the input file.
+    fn reload(&mut self) -> Result<()> {
+        let input = inputs::read(&self.path)?;
+
+        self.session = self.day.repl.map(|open| open(&input)).transpose()?;
+        self.input = input;
+
+        Ok(())
+    }
+
+    /// Reads and runs commands until the standard input is closed or the REPL is told to quit.
+    fn read_commands(&mut self) -> Result<()> {
+        println!("day {} loaded from '{}', type `help` for a list of commands", self.day.number, self.path.display());
+
+        let mut lines = stdin().lock().lines();
+
+        loop {
+            print!("day {}> ", self.day.number);
+            stdout().flush()?;
+
+            let Some(line) = lines.next().transpose()? else { break };
+            let line = line.trim();
+            let (command, arguments) = line.split_once(' ').unwrap_or((line, ""));
+
+            if matches!(command, "quit" | "exit") {
+                break;
+            }
+
+            match catch_unwind(AssertUnwindSafe(|| self.run(command, arguments))) {
+                Ok(Ok(output)) if output.is_empty() => {}
+                Ok(Ok(output)) => println!("{output}"),
+                Ok(Err(error)) => println!("error: {error:#}"),
+                Err(payload) => println!("panic: {}", panic_message(payload.as_ref())),
+            }
+        }
+
+        // Makes sure the shell's prompt doesn't end up on the same line as ours.
+        println!();
+
+        Ok(())
+    }
+
+    /// Runs a single command, returning its output.
+    fn run(&mut self, command: &str, arguments: &str) -> Result<String> {
+        match command {
+            "" => Ok(String::new()),
+            "help" => Ok(self.help()),
+            "reload" => self.reload().map(|_| format!("reloaded '{}'", self.path.display())),
+            "part" => {
+                let solution = self.day.solution(arguments.trim().parse()?)?;
+                let start = Instant::now();
+                let outcome = Outcome::of(solution, &self.input);
+
+                Ok(format!("{outcome} in {:.2?}", start.elapsed()))
+            }
+            command => match self.session.as_mut().and_then(|v| v.run(command, arguments)) {
+                Some(output) => output,
+                None => bail!("unknown command '{command}', try `help`"),
+            },
+        }
+    }
+
+    /// Lists every available command.
+    fn help(&self) -> String {
+        let day_commands = self.session.as_ref().map_or(&[][..], |v| v.commands());
+        let width = GENERIC_COMMANDS.iter().chain(day_commands).map(|(usage, _)| usage.len()).max().unwrap_or(0);
+
+        GENERIC_COMMANDS
+            .iter()
+            .chain(day_commands)
+            .map(|(usage, description)| format!("  {usage:<width$}  {description}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
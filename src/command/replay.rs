@@ -0,0 +1,29 @@
+use std::io::{Write, stdout};
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::Day;
+use crate::arguments::Arguments;
+use crate::crash::Bundle;
+
+/// Re-runs the exact case captured within a crash bundle.
+///
+/// Usage: `replay <bundle directory>`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let directory: PathBuf = arguments.next("bundle directory")?;
+
+    arguments.finish()?;
+
+    let (bundle, input) = Bundle::read(&directory)?;
+    let solution = Day::find(bundle.day)?.solution(bundle.part)?;
+
+    eprintln!("replaying day {}, part {} ({} from commit {})", bundle.day, bundle.part, bundle.kind, bundle.commit);
+
+    // We let this fail however it wants to, since that's the whole point.
+    let answer = solution(&input)?;
+
+    eprintln!("the {} no longer reproduces", bundle.kind);
+
+    stdout().write_fmt(format_args!("{answer}\n")).map_err(Into::into)
+}
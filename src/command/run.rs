@@ -0,0 +1,144 @@
+use std::fs::File;
+use std::io::{Write, stderr, stdout};
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
+
+use crate::arguments::Arguments;
+use crate::cache::Cache;
+use crate::crash::{self, Bundle};
+use crate::error::Error;
+use crate::utility::json::Json;
+use crate::{Day, identify, inputs, trace};
+
+/// Runs a single solution, printing its result.
+///
+/// Usage: `[run] <day #> <solution #> [<use example data? (0 or 1)>] [--input <path>] [--trace | --trace-json]
+/// [--trace-output <path>] [--no-crash-input] [--cache | --no-cache] [--json]`
+///
+/// Alternatively, `[run] --auto --input <path> <solution #>` guesses the day from the input's structure.
+///
+/// Passing `--json` prints the result as a single JSON object instead, which describes the error if there is one.
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let json = arguments.flag("--json");
+    let result = self::solve(arguments);
+
+    let output = match (&result, json) {
+        (Ok(solved), false) if solved.cached => format!("{} (cached)", solved.answer),
+        (Ok(solved), false) => solved.answer.to_string(),
+        (Ok(solved), true) => Json::object([
+            ("day", Json::from(solved.day)),
+            ("part", Json::from(solved.part)),
+            ("answer", Json::from(solved.answer)),
+            ("cached", Json::from(solved.cached)),
+        ])
+        .to_string(),
+        (Err(error), true) => Json::object(Error::json_entries_of(error)).to_string(),
+        // The error is printed on its way out of the program instead.
+        (Err(_), false) => return result.map(drop),
+    };
+
+    stdout().write_fmt(format_args!("{output}\n"))?;
+
+    result.map(drop)
+}
+
+/// A solution's answer.
+#[derive(Clone, Copy, Debug)]
+struct Solved {
+    /// The day that was solved.
+    day: u8,
+    /// The part that was solved.
+    part: u8,
+    /// The answer.
+    answer: u64,
+    /// Whether the answer came from the cache.
+    cached: bool,
+}
+
+/// Finds and runs the solution described by the given arguments.
+fn solve(mut arguments: Arguments) -> Result<Solved> {
+    let trace_format = match (arguments.flag("--trace"), arguments.flag("--trace-json")) {
+        (_, true) => Some(trace::Format::Json),
+        (true, false) => Some(trace::Format::Text),
+        (false, false) => None,
+    };
+    let trace_output: Option<PathBuf> = arguments.option("--trace-output")?;
+    let skip_input_copy = arguments.flag("--no-crash-input");
+    let input_override: Option<PathBuf> = arguments.option("--input")?;
+    let auto = arguments.flag("--auto");
+    let cache = Cache::from_arguments(&mut arguments)?;
+
+    if auto && input_override.is_none() {
+        bail!("guessing the day requires an input file, given with '--input <path>'");
+    }
+
+    let expected_day: Option<u8> = if auto { None } else { Some(arguments.next("day")?) };
+    let expected_solution: u8 = arguments.next("solution")?;
+    let use_examples = !auto
+        && arguments.next_optional::<String>("example flag")?.is_some_and(|v| v.parse::<u8>().is_ok_and(|v| v == 1));
+
+    arguments.finish()?;
+
+    if let Some(format) = trace_format {
+        let output: Box<dyn Write + Send> = match trace_output {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(stderr()),
+        };
+
+        trace::enable(format, output);
+    }
+
+    // The part is checked before the input is read, since a missing part is a better error than a missing input.
+    let (day, solution, input_path, input) = match (expected_day, input_override) {
+        (Some(day), Some(path)) => {
+            let day = Day::find(day)?;
+            let solution = day.solution(expected_solution)?;
+
+            (day, solution, path.clone(), inputs::read(&path)?)
+        }
+        (Some(day), None) => {
+            let day = Day::find(day)?;
+            let solution = day.solution(expected_solution)?;
+
+            (day, solution, day.input_path(use_examples), day.read_input(use_examples)?)
+        }
+        (None, Some(path)) => {
+            let input = inputs::read(&path)?;
+            let day = identify::guess(&input)?;
+
+            eprintln!("guessed that '{}' belongs to day {}", path.display(), day.number);
+
+            (day, day.solution(expected_solution)?, path, input)
+        }
+        (None, None) => unreachable!("the input file is required when guessing the day"),
+    };
+
+    // A cached answer wouldn't produce any trace events, which would make tracing pretty pointless.
+    if let Some(answer) =
+        cache.as_ref().filter(|_| trace_format.is_none()).and_then(|v| v.get(day.number, expected_solution, &input))
+    {
+        return Ok(Solved { day: day.number, part: expected_solution, answer, cached: true });
+    }
+
+    crash::install_hook();
+
+    // If anything goes wrong, we bundle up everything needed to reproduce it, so that nobody has to go asking for it.
+    let answer = match crash::catch(|| solution(&input)) {
+        Ok(answer) => answer,
+        Err(crash) => {
+            let bundle = Bundle::new(day.number, expected_solution, &input_path, &input, &crash);
+            let directory = bundle.write(&input, &crash, !skip_input_copy)?;
+
+            eprintln!("{crash}\n\na crash bundle was written to '{}'", directory.display());
+
+            return Err(crash.cause.into());
+        }
+    };
+
+    if let Some(cache) = cache {
+        cache.insert(day.number, expected_solution, &input, answer);
+    }
+
+    Ok(Solved { day: day.number, part: expected_solution, answer, cached: false })
+}
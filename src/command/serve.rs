@@ -0,0 +1,353 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::arguments::Arguments;
+use crate::crash::{self, Kind};
+use crate::error::Error;
+use crate::utility::json::Json;
+use crate::{DAYS, Day};
+
+/// The port used if none is specified.
+const DEFAULT_PORT: u16 = 8024;
+/// The largest accepted request body, in bytes, if none is specified.
+const DEFAULT_MAX_BODY: usize = 1024 * 1024;
+/// The longest a solution may run for, in seconds, if none is specified.
+const DEFAULT_TIMEOUT: u64 = 10;
+/// The most connections that are handled at once if no limit is specified.
+const DEFAULT_MAX_CONNECTIONS: usize = 64;
+/// The largest accepted request line or header section, in bytes.
+const MAX_HEAD: u64 = 16 * 1024;
+/// How long we wait on a slow client before giving up on it.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves every registered solution over a tiny HTTP/1.1 API.
+///
+/// - `GET /days` lists every registered day and its solutions.
+/// - `POST /solve/{day}/{part}` runs a solution using the request body as its input.
+///
+/// Only so many connections and solutions are allowed at once, and anything past that is turned away with a `503`.
+/// Solutions that time out still count against the limit until they actually finish, since there's no way to stop
+/// them early.
+///
+/// Usage: `serve [--host <address>] [--port <N>] [--max-body <bytes>] [--timeout <seconds>] [--max-solves <N>]
+/// [--max-connections <N>]`
+pub fn run(mut arguments: Arguments) -> Result<()> {
+    let host = arguments.option("--host")?.unwrap_or_else(|| "127.0.0.1".to_string());
+    let port = arguments.option("--port")?.unwrap_or(DEFAULT_PORT);
+    let limits = Limits {
+        max_body: arguments.option("--max-body")?.unwrap_or(DEFAULT_MAX_BODY),
+        timeout: Duration::from_secs(arguments.option("--timeout")?.unwrap_or(DEFAULT_TIMEOUT)),
+    };
+    // Solutions are CPU-bound, so there's no point in running more of them than there are cores.
+    let solves = Slots::new(
+        arguments
+            .option("--max-solves")?
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |v| v.get())),
+    );
+    let connections = Slots::new(arguments.option("--max-connections")?.unwrap_or(DEFAULT_MAX_CONNECTIONS));
+
+    arguments.finish()?;
+
+    let listener = TcpListener::bind((host.as_str(), port))?;
+
+    println!("listening on http://{}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("failed to accept connection: {error}");
+
+                continue;
+            }
+        };
+
+        let Some(slot) = connections.claim() else {
+            // Nobody's reading the request, so there's no reason to wait around for it either.
+            _ = Response::error(503, "too many connections, try again later").write_to(&mut &stream);
+
+            continue;
+        };
+        let solves = solves.clone();
+
+        // Every connection gets its own thread, since a slow solution shouldn't hold up anybody else's request.
+        std::thread::spawn(move || {
+            if let Err(error) = self::handle(stream, limits, &solves) {
+                eprintln!("failed to handle connection: {error}");
+            }
+
+            drop(slot);
+        });
+    }
+
+    Ok(())
+}
+
+/// The limits placed on every request.
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    /// The largest accepted request body, in bytes.
+    max_body: usize,
+    /// The longest a solution may run for.
+    timeout: Duration,
+}
+
+/// A fixed number of slots that work has to claim before it's allowed to run.
+#[derive(Clone, Debug)]
+struct Slots {
+    /// The number of slots that are currently claimed, shared between every clone.
+    used: Arc<AtomicUsize>,
+    /// The total number of slots.
+    limit: usize,
+}
+
+impl Slots {
+    /// Creates a new set of [`Slots`], none of which are claimed.
+    fn new(limit: usize) -> Self {
+        Self { used: Arc::new(AtomicUsize::new(0)), limit }
+    }
+
+    /// Claims a slot, returning `None` if every one is already taken.
+    ///
+    /// The slot is freed again once the returned [`Slot`] is dropped.
+    fn claim(&self) -> Option<Slot> {
+        self.used.fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| (v < self.limit).then_some(v + 1)).ok()?;
+
+        Some(Slot(Arc::clone(&self.used)))
+    }
+}
+
+/// A claimed slot, which is freed when dropped.
+#[derive(Debug)]
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// A parsed HTTP request.
+#[derive(Clone, Debug)]
+struct Request {
+    /// The request's method.
+    method: String,
+    /// The request's path, without any query string.
+    path: String,
+    /// The request's body.
+    body: Vec<u8>,
+}
+
+/// An HTTP response, which always has a JSON body.
+#[derive(Clone, Debug)]
+struct Response {
+    /// The response's status code.
+    status: u16,
+    /// The response's body.
+    body: Json,
+}
+
+impl Response {
+    /// Creates a new successful [`Response`].
+    fn ok(body: Json) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// Creates a new [`Response`] containing an error message.
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self { status, body: Json::object([("error", message.into())]) }
+    }
+
+    /// Returns the reason phrase of this response's status code.
+    const fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            411 => "Length Required",
+            413 => "Content Too Large",
+            422 => "Unprocessable Content",
+            431 => "Request Header Fields Too Large",
+            500 => "Internal Server Error",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Unknown",
+        }
+    }
+
+    /// Writes this response to the given stream.
+    fn write_to(&self, stream: &mut impl Write) -> std::io::Result<()> {
+        let body = self.body.to_string();
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.status,
+            self.reason(),
+            body.len(),
+        )?;
+
+        stream.flush()
+    }
+}
+
+/// Handles a single connection, which only ever carries a single request.
+fn handle(mut stream: TcpStream, limits: Limits, solves: &Slots) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let start = Instant::now();
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let (label, response) = match self::read_request(&mut reader, limits.max_body) {
+        Ok(request) => (format!("{} {}", request.method, request.path), self::route(request, limits, solves)),
+        Err(response) => ("<invalid request>".to_string(), response),
+    };
+
+    println!("{label} -> {} ({:?})", response.status, start.elapsed());
+
+    response.write_to(&mut stream).map_err(Into::into)
+}
+
+/// Reads a request from the given stream, returning an error response if it's malformed or too large.
+fn read_request(reader: &mut impl BufRead, max_body: usize) -> Result<Request, Response> {
+    let mut head = reader.take(MAX_HEAD);
+    let mut line = String::new();
+
+    let read_line = |head: &mut dyn BufRead, line: &mut String| -> Result<(), Response> {
+        line.clear();
+
+        match head.read_line(line) {
+            Ok(_) if line.ends_with('\n') => Ok(()),
+            Ok(_) => Err(Response::error(431, "the request head is too large or incomplete")),
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                Err(Response::error(408, "timed out while reading the request"))
+            }
+            Err(error) => Err(Response::error(400, error.to_string())),
+        }
+    };
+
+    read_line(&mut head, &mut line)?;
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(Response::error(400, "malformed request line"));
+    };
+    let method = method.to_string();
+    let path = target.split_once('?').map_or(target, |v| v.0).to_string();
+
+    let mut content_length = None;
+
+    loop {
+        read_line(&mut head, &mut line)?;
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(Response::error(400, "malformed header"));
+        };
+
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let Ok(length) = value.trim().parse::<usize>() else {
+                return Err(Response::error(400, "invalid content length"));
+            };
+
+            content_length = Some(length);
+        }
+    }
+
+    let body = match content_length {
+        None if method == "POST" => return Err(Response::error(411, "a content length is required")),
+        None | Some(0) => Vec::new(),
+        Some(length) if length > max_body => {
+            return Err(Response::error(413, format!("the request body must not exceed {max_body} bytes")));
+        }
+        Some(length) => {
+            let mut body = vec![0; length];
+
+            reader.read_exact(&mut body).map_err(|v| Response::error(400, v.to_string()))?;
+
+            body
+        }
+    };
+
+    Ok(Request { method, path, body })
+}
+
+/// Routes the given request to its handler.
+fn route(request: Request, limits: Limits, solves: &Slots) -> Response {
+    let segments: Vec<&str> = request.path.split('/').filter(|v| !v.is_empty()).collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["days"]) => self::list_days(),
+        ("POST", ["solve", day, part]) => self::solve(day, part, request.body, limits.timeout, solves),
+        (_, ["days"] | ["solve", _, _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+/// Lists every registered day.
+fn list_days() -> Response {
+    let days =
+        DAYS.iter().map(|day| Json::object([("day", Json::from(day.number)), ("parts", Json::from(vec![1, 2]))]));
+
+    Response::ok(Json::object([("days", Json::Array(days.collect()))]))
+}
+
+/// Runs a solution using the given body as its input.
+fn solve(day: &str, part: &str, body: Vec<u8>, timeout: Duration, solves: &Slots) -> Response {
+    let (Ok(day), Ok(part)) = (day.parse::<u8>(), part.parse::<u8>()) else {
+        return Response::error(404, "the day and part must both be numbers");
+    };
+    let solution = match Day::find(day).and_then(|v| v.solution(part)) {
+        Ok(solution) => solution,
+        Err(error) => return Response { status: 404, body: Json::object(Error::json_entries_of(&error)) },
+    };
+    let Ok(input) = String::from_utf8(body) else {
+        return Response::error(400, "the request body must be valid UTF-8");
+    };
+
+    let Some(slot) = solves.claim() else {
+        return Response::error(503, "too many solutions are already running, try again later");
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let start = Instant::now();
+
+    // There's no way to stop a thread from the outside, so a timed-out solution is left to finish in the background.
+    // The answer just gets thrown away once it's done, and its slot stays claimed until then so they can't pile up.
+    std::thread::spawn(move || {
+        _ = sender.send(crash::catch(|| solution(&input)));
+
+        drop(slot);
+    });
+
+    let result = match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(_) => return Response::error(504, format!("the solution did not finish within {timeout:?}")),
+    };
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let (status, result) = match result {
+        Ok(answer) => (200, vec![("answer", Json::from(answer))]),
+        Err(crash) if crash.kind == Kind::Panic => (500, crash.cause.json_entries()),
+        Err(crash) => (422, crash.cause.json_entries()),
+    };
+
+    let mut entries = vec![("day", Json::from(day)), ("part", Json::from(part))];
+
+    entries.extend(result);
+    entries.push(("elapsed_ms", Json::from(elapsed_ms)));
+
+    Response { status, body: Json::object(entries) }
+}
@@ -0,0 +1,272 @@
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::panic::{AssertUnwindSafe, catch_unwind, set_hook, take_hook};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+
+use crate::error::Error;
+use crate::inputs;
+use crate::outcome::panic_message;
+use crate::utility::hash::fnv1a_hex;
+use crate::utility::json::Json;
+
+/// The directory that every crash bundle is written into.
+///
+/// It's found from the crate's root rather than the working directory, so bundles all end up in the same place no
+/// matter where the program is run from.
+const BUNDLE_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/crash");
+/// The name of the file containing a bundle's details, as a JSON object.
+const DETAILS_FILE: &str = "bundle.json";
+/// The name of the file containing a copy of a bundle's input.
+const INPUT_FILE: &str = "input.txt";
+/// The name of the file containing a bundle's error or panic message.
+const MESSAGE_FILE: &str = "message.txt";
+/// The name of the file containing a bundle's backtrace.
+const BACKTRACE_FILE: &str = "backtrace.txt";
+
+thread_local! {
+    /// The backtrace of the most recent panic on this thread, captured by the hook installed with [`install_hook`].
+    ///
+    /// Solutions can panic on several threads at once (within `serve` or `batch`), so each one keeps its own.
+    static LAST_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Installs a panic hook that remembers the backtrace of every panic, so that it can be written into a bundle.
+///
+/// The previous hook is still called afterwards, so panics are reported just like they were before.
+pub fn install_hook() {
+    let previous = take_hook();
+
+    set_hook(Box::new(move |info| {
+        LAST_BACKTRACE.set(Some(Backtrace::force_capture().to_string()));
+
+        previous(info);
+    }));
+}
+
+/// How a solution failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Error,
+    Panic,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Panic => "panic",
+        })
+    }
+}
+
+/// A solution's failure, along with everything we know about how it happened.
+#[derive(Debug)]
+pub struct Crash {
+    /// How the solution failed.
+    pub kind: Kind,
+    /// The error or panic message.
+    pub message: String,
+    /// The backtrace of the error or panic, if one was captured.
+    pub backtrace: String,
+    /// What went wrong, which is always a solver error for panics.
+    pub cause: Error,
+}
+
+impl Display for Crash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            Kind::Error => write!(f, "the solution failed: {}", self.message),
+            Kind::Panic => write!(f, "the solution panicked: {}", self.message),
+        }
+    }
+}
+
+/// Runs the given solution, catching any errors or panics as a [`Crash`].
+///
+/// Panics only have their backtraces captured if [`install_hook`] was called beforehand.
+pub fn catch(solution: impl FnOnce() -> Result<u64>) -> Result<u64, Crash> {
+    match catch_unwind(AssertUnwindSafe(solution)) {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(error)) => Err(Crash {
+            kind: Kind::Error,
+            message: format!("{error:#}"),
+            backtrace: error.backtrace().to_string(),
+            cause: Error::from_solution(error),
+        }),
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+
+            Err(Crash {
+                kind: Kind::Panic,
+                cause: Error::Solver(format!("the solution panicked: {message}")),
+                message,
+                backtrace: LAST_BACKTRACE.take().unwrap_or_default(),
+            })
+        }
+    }
+}
+
+/// Everything needed to reproduce a crash.
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    /// The day that crashed.
+    pub day: u8,
+    /// The part that crashed.
+    pub part: u8,
+    /// The path of the input file that was used.
+    pub input_path: PathBuf,
+    /// The hash of the input file's contents.
+    pub input_hash: String,
+    /// The git commit that the program was run from.
+    pub commit: String,
+    /// The program's command-line arguments.
+    pub arguments: Vec<String>,
+    /// How the solution failed.
+    pub kind: Kind,
+}
+
+impl Bundle {
+    /// Creates a new [`Bundle`] for the given crash, using the current process' arguments and git commit.
+    pub fn new(day: u8, part: u8, input_path: &Path, input: &str, crash: &Crash) -> Self {
+        Self {
+            day,
+            part,
+            input_path: input_path.to_path_buf(),
+            input_hash: fnv1a_hex(input.as_bytes()),
+            commit: self::git_commit(),
+            arguments: std::env::args().collect(),
+            kind: crash.kind,
+        }
+    }
+
+    /// Writes this bundle into a new directory, returning its path.
+    ///
+    /// The input is copied into the bundle unless `copy_input` is `false`, in which case only its hash is kept.
+    pub fn write(&self, input: &str, crash: &Crash, copy_input: bool) -> Result<PathBuf> {
+        let directory = self.create_directory()?;
+
+        let details = Json::object([
+            ("day", Json::from(self.day)),
+            ("part", Json::from(self.part)),
+            ("input", Json::from(self.input_path.display().to_string())),
+            ("input_hash", Json::from(self.input_hash.as_str())),
+            ("commit", Json::from(self.commit.as_str())),
+            ("arguments", Json::from(self.arguments.clone())),
+            ("kind", Json::from(self.kind.to_string())),
+        ]);
+
+        std::fs::write(directory.join(DETAILS_FILE), format!("{details}\n"))?;
+        std::fs::write(directory.join(MESSAGE_FILE), format!("{}\n", crash.message))?;
+        std::fs::write(directory.join(BACKTRACE_FILE), &crash.backtrace)?;
+
+        if copy_input {
+            std::fs::write(directory.join(INPUT_FILE), input)?;
+        }
+
+        Ok(directory)
+    }
+
+    /// Creates a new, empty directory for this bundle, returning its path.
+    ///
+    /// Bundles are named after when they were written, so a counter is added whenever another bundle (maybe from
+    /// another process) already took this second's name.
+    fn create_directory(&self) -> Result<PathBuf> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |v| v.as_secs());
+        let name = format!("day-{}-part-{}-{timestamp}", self.day, self.part);
+
+        std::fs::create_dir_all(BUNDLE_DIRECTORY)?;
+
+        for attempt in 0 .. {
+            let directory = match attempt {
+                0 => Path::new(BUNDLE_DIRECTORY).join(&name),
+                _ => Path::new(BUNDLE_DIRECTORY).join(format!("{name}-{attempt}")),
+            };
+
+            match std::fs::create_dir(&directory) {
+                Ok(()) => return Ok(directory),
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        unreachable!("there's always another attempt")
+    }
+
+    /// Reads a bundle from the given directory, along with its input.
+    ///
+    /// If the bundle doesn't contain a copy of its input, the original input file is read instead, so long as its
+    /// contents still match the bundle's hash.
+    pub fn read(directory: &Path) -> Result<(Self, String)> {
+        let details = std::fs::read_to_string(directory.join(DETAILS_FILE))
+            .with_context(|| format!("'{}' is not a crash bundle", directory.display()))?;
+        let details = Json::parse(&details).context("the crash bundle's details aren't valid JSON")?;
+        let get = |key: &str| details.get(key).with_context(|| format!("the crash bundle is missing its '{key}'"));
+        let string = |key: &str| {
+            get(key)?.as_str().map(str::to_string).with_context(|| format!("the crash bundle's '{key}' isn't a string"))
+        };
+        let number = |key: &str| {
+            get(key)?
+                .as_integer()
+                .and_then(|v| u8::try_from(v).ok())
+                .with_context(|| format!("the crash bundle's '{key}' isn't a small number"))
+        };
+
+        let bundle = Self {
+            day: number("day")?,
+            part: number("part")?,
+            input_path: PathBuf::from(string("input")?),
+            input_hash: string("input_hash")?,
+            commit: string("commit")?,
+            arguments: get("arguments")?
+                .as_array()
+                .and_then(|v| v.iter().map(|v| v.as_str().map(str::to_string)).collect())
+                .context("the crash bundle's 'arguments' aren't an array of strings")?,
+            kind: match string("kind")?.as_str() {
+                "error" => Kind::Error,
+                "panic" => Kind::Panic,
+                kind => bail!("unknown crash kind '{kind}'"),
+            },
+        };
+
+        let input = match std::fs::read_to_string(directory.join(INPUT_FILE)) {
+            Ok(input) => input,
+            Err(_) => {
+                let input = inputs::read(&bundle.input_path).with_context(|| {
+                    format!("the bundle has no input copy, and '{}' could not be read", bundle.input_path.display())
+                })?;
+
+                if fnv1a_hex(input.as_bytes()) != bundle.input_hash {
+                    bail!("the bundle has no input copy, and '{}' has changed since", bundle.input_path.display());
+                }
+
+                input
+            }
+        };
+
+        Ok((bundle, input))
+    }
+}
+
+/// Returns the current git commit, marked as dirty if there are uncommitted changes.
+fn git_commit() -> String {
+    let run = |arguments: &[&str]| {
+        Command::new("git")
+            .args(arguments)
+            .output()
+            .ok()
+            .filter(|v| v.status.success())
+            .map(|v| String::from_utf8_lossy(&v.stdout).trim().to_string())
+    };
+
+    match (run(&["rev-parse", "HEAD"]), run(&["status", "--porcelain"])) {
+        (Some(commit), Some(status)) if !status.is_empty() => format!("{commit} (dirty)"),
+        (Some(commit), _) => commit,
+        (None, _) => "unknown".to_string(),
+    }
+}
@@ -1,35 +1,72 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::fmt::Write;
 
 use anyhow::Result;
 
-use crate::SolutionResult;
+use crate::error::Error;
+use crate::identify::{self, line_ratio};
+use crate::repl::Open;
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 type Input = (Box<[u32]>, Box<[u32]>);
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    let iterator = BufReader::new(File::open(path)?).lines();
+fn input(string: &str) -> Result<Input> {
+    let iterator = string.lines();
     let capacity = iterator.size_hint().1.unwrap_or_else(|| iterator.size_hint().0);
 
     let mut lhs_array = Vec::<u32>::with_capacity(capacity);
     let mut rhs_array = Vec::<u32>::with_capacity(capacity);
 
     // Split and parse every line into two separate numbers.
-    for result in iterator {
-        let line = result?;
-        let (lhs_str, rhs_str) = line.split_once("   ").unwrap();
-        //            This is exactly three spaces ^
-
-        lhs_array.push(lhs_str.parse()?);
-        rhs_array.push(rhs_str.parse()?);
+    for (index, line) in iterator.enumerate() {
+        let Some((lhs_str, rhs_str)) = line.split_once("   ") else {
+            //                              This is exactly three spaces ^
+            return Err(Error::parse_at(index + 1, "expected two numbers separated by three spaces").into());
+        };
+
+        lhs_array.push(lhs_str.parse().map_err(|v| Error::parse_within(index + 1, line, lhs_str, v))?);
+        rhs_array.push(rhs_str.parse().map_err(|v| Error::parse_within(index + 1, line, rhs_str, v))?);
     }
 
     Ok((lhs_array.into_boxed_slice(), rhs_array.into_boxed_slice()))
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    let (mut lhs_array, mut rhs_array) = self::input(path)?;
+/// Generates a random list of location ID pairs.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    let mut lhs_array = Vec::with_capacity(size);
+    let mut output = String::with_capacity(size * 14);
+
+    for _ in 0 .. size {
+        let lhs_value = random.between(10_000, 99_999);
+
+        // Re-use previous values every so often, otherwise the similarity score would almost always be zero.
+        let rhs_value = match random.chance(1, 3) {
+            true => random.choose(&lhs_array).copied().unwrap_or(lhs_value),
+            false => random.between(10_000, 99_999),
+        };
+
+        lhs_array.push(lhs_value);
+
+        _ = writeln!(output, "{lhs_value}   {rhs_value}");
+    }
+
+    output
+}
+
+/// Returns how likely it is that the input is a list of location ID pairs.
+pub fn identify(string: &str) -> f64 {
+    // Exactly two integer columns on every line.
+    line_ratio(string, |line| {
+        let mut columns = line.split_whitespace();
+
+        columns.next().is_some_and(|v| identify::is_integer_list(v, " "))
+            && columns.next().is_some_and(|v| identify::is_integer_list(v, " "))
+            && columns.next().is_none()
+    })
+}
+
+pub fn solution_1(string: &str) -> SolutionResult {
+    let (mut lhs_array, mut rhs_array) = self::input(string)?;
     let mut differences = Vec::with_capacity(lhs_array.len());
 
     // Sorting these automatically fills the requirement of matching lesser values together.
@@ -46,8 +83,8 @@ pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
     Ok(differences.into_iter().sum())
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
-    let (lhs_array, rhs_array) = self::input(path)?;
+pub fn solution_2(string: &str) -> SolutionResult {
+    let (lhs_array, rhs_array) = self::input(string)?;
     let mut multiples = Vec::with_capacity(lhs_array.len());
 
     for lhs_value in lhs_array {
@@ -59,3 +96,40 @@ pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
 
     Ok(multiples.into_iter().sum())
 }
+
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+fn reference_1(string: &str) -> SolutionResult {
+    let (lhs_array, rhs_array) = self::input(string)?;
+    let (mut lhs_array, mut rhs_array) = (lhs_array.into_vec(), rhs_array.into_vec());
+    let mut total = 0;
+
+    // Repeatedly pair up the smallest remaining value of each list.
+    while let (Some(lhs_value), Some(rhs_value)) = (lhs_array.iter().min().copied(), rhs_array.iter().min().copied()) {
+        lhs_array.swap_remove(lhs_array.iter().position(|v| *v == lhs_value).unwrap());
+        rhs_array.swap_remove(rhs_array.iter().position(|v| *v == rhs_value).unwrap());
+
+        total += lhs_value.abs_diff(rhs_value) as u64;
+    }
+
+    Ok(total)
+}
+
+fn reference_2(string: &str) -> SolutionResult {
+    let (lhs_array, rhs_array) = self::input(string)?;
+    let mut total = 0;
+
+    for lhs_value in &lhs_array {
+        for rhs_value in &rhs_array {
+            if lhs_value == rhs_value {
+                total += *lhs_value as u64;
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+/// This day doesn't have any commands of its own within the REPL.
+pub const REPL: Option<Open> = None;
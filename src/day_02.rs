@@ -1,23 +1,22 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
 use anyhow::Result;
 
-use crate::SolutionResult;
+use crate::error::Error;
+use crate::identify::{self, line_ratio};
+use crate::repl::Open;
+use crate::trace::trace;
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 // Awful or fantastic type alias, depending on how you look at it.
 type Input = <Box<[Box<[u8]>]> as IntoIterator>::IntoIter;
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    let iterator = BufReader::new(File::open(path)?).lines();
+fn input(string: &str) -> Result<Input> {
+    let iterator = string.lines();
     let capacity = iterator.size_hint().1.unwrap_or_else(|| iterator.size_hint().0);
 
     let mut report_array = Vec::with_capacity(capacity);
 
-    for result in iterator {
-        let line = result?;
-
+    for (index, line) in iterator.enumerate() {
         // Every line is split into a list of integers.
         let iterator = line.split(' ');
         let capacity = iterator.size_hint().1.unwrap_or_else(|| iterator.size_hint().0);
@@ -25,7 +24,7 @@ fn input(path: impl AsRef<Path>) -> Result<Input> {
         let mut value_array = Vec::with_capacity(capacity);
 
         for substring in line.split(' ') {
-            value_array.push(substring.parse()?);
+            value_array.push(substring.parse().map_err(|v| Error::parse_within(index + 1, line, substring, v))?);
         }
 
         report_array.push(value_array.into_boxed_slice());
@@ -34,26 +33,93 @@ fn input(path: impl AsRef<Path>) -> Result<Input> {
     Ok(report_array.into_iter())
 }
 
+/// Generates a random list of reports, most of which are nearly safe.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    let mut output = String::with_capacity(size * 16);
+
+    for _ in 0 .. size {
+        let length = random.between(5, 8);
+        let descending = random.chance(1, 2);
+        let mut level = random.between(20, 70) as i64;
+        let mut report = Vec::with_capacity(length as usize);
+
+        for _ in 0 .. length {
+            report.push(level.to_string());
+
+            // Mostly step within the safe range, but occasionally stall or jump to make things interesting.
+            let step = match random.below(10) {
+                0 => 0,
+                1 => random.between(4, 7) as i64,
+                _ => random.between(1, 3) as i64,
+            };
+
+            level = if descending ^ random.chance(1, 12) { level - step } else { level + step }.clamp(1, 99);
+        }
+
+        output.push_str(&report.join(" "));
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Returns how likely it is that the input is a list of reports.
+pub fn identify(string: &str) -> f64 {
+    // Reports have a handful of small levels each, which sets them apart from day one's two large columns.
+    line_ratio(string, |line| {
+        identify::is_integer_list(line, " ")
+            && line.split(' ').count() >= 3
+            && line.split(' ').all(|v| v.parse::<u8>().is_ok())
+    })
+}
+
 /// Check if the array is sorted either forwards *or* backwards.
 fn check_sorting(array: &[u8]) -> bool {
     array.is_sorted() || array.iter().rev().is_sorted()
 }
 
+/// Returns the index of the first level that makes the report unsafe.
+///
+/// This is only used for tracing, so it doesn't need to be fast.
+fn first_unsafe_index(array: &[u8]) -> Option<usize> {
+    let ascending = array.first() < array.last();
+
+    array
+        .windows(2)
+        .position(|v| !(1 ..= 3).contains(&v[0].abs_diff(v[1])) || (v[0] < v[1]) != ascending)
+        .map(|v| v + 1)
+}
+
 /// Check if the maximum difference between two consecutive elements is within 1..=3.
 fn check_levels(array: &[u8]) -> bool {
     // The index operators in the map are safe because `.windows()` always returns a slice of length 2.
     array.windows(2).map(|v| v[0].abs_diff(v[1])).all(|v| v > 0 && v <= 3)
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    // Love to see the one-liner.
-    Ok(self::input(path)?.filter(|v| check_sorting(v) && check_levels(v)).count() as _)
+pub fn solution_1(string: &str) -> SolutionResult {
+    // Love to see the one-liner. Well, it *was* a one-liner before tracing came along.
+    Ok(self::input(string)?
+        .enumerate()
+        .filter(|(index, v)| {
+            let is_safe = check_sorting(v) && check_levels(v);
+
+            if !is_safe {
+                trace!(
+                    "unsafe_report",
+                    "report {index} unsafe at index {}",
+                    self::first_unsafe_index(v).unwrap_or_default()
+                );
+            }
+
+            is_safe
+        })
+        .count() as _)
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
+pub fn solution_2(string: &str) -> SolutionResult {
     let mut correct = 0;
 
-    for report_array in self::input(path)? {
+    'outer: for (report_index, report_array) in self::input(string)?.enumerate() {
         if self::check_sorting(&report_array) && self::check_levels(&report_array) {
             correct += 1;
 
@@ -73,12 +139,52 @@ pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
             buffer.extend(report_array.iter().copied().enumerate().filter_map(|(i, n)| (i != index).then_some(n)));
 
             if self::check_sorting(&buffer) && self::check_levels(&buffer) {
+                trace!("dampened_report", "report {report_index} made safe by removing index {index}");
+
                 correct += 1;
 
-                break;
+                continue 'outer;
             }
         }
+
+        trace!(
+            "unsafe_report",
+            "report {report_index} unsafe at index {}",
+            self::first_unsafe_index(&report_array).unwrap_or_default()
+        );
     }
 
     Ok(correct)
 }
+
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+/// Checks whether a report is safe by looking at every step between its levels directly.
+fn reference_is_safe(report: &[u8]) -> bool {
+    let steps: Vec<i16> = report.windows(2).map(|v| v[1] as i16 - v[0] as i16).collect();
+
+    steps.iter().all(|v| (1 ..= 3).contains(v)) || steps.iter().all(|v| (-3 ..= -1).contains(v))
+}
+
+fn reference_1(string: &str) -> SolutionResult {
+    Ok(self::input(string)?.filter(|v| self::reference_is_safe(v)).count() as _)
+}
+
+fn reference_2(string: &str) -> SolutionResult {
+    Ok(self::input(string)?
+        .filter(|report| {
+            self::reference_is_safe(report)
+                || (0 .. report.len()).any(|index| {
+                    let mut report = report.to_vec();
+
+                    report.remove(index);
+
+                    self::reference_is_safe(&report)
+                })
+        })
+        .count() as _)
+}
+
+/// This day doesn't have any commands of its own within the REPL.
+pub const REPL: Option<Open> = None;
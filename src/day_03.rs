@@ -1,12 +1,13 @@
 use std::error::Error;
-use std::fs::read_to_string;
 use std::iter::Peekable;
-use std::path::Path;
 use std::str::FromStr;
 
 use anyhow::{Result, bail};
 
-use crate::SolutionResult;
+use crate::repl::Open;
+use crate::trace::trace;
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 // Hello, chat. Today we're going to misuse the `FromStr` trait because I like using `.parse()`.
 //
@@ -73,8 +74,53 @@ impl FromStr for Enable {
     }
 }
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    Ok(read_to_string(path)?.into_boxed_str())
+fn input(string: &str) -> Result<Input> {
+    Ok(Box::from(string))
+}
+
+/// Generates a random chunk of corrupted memory containing the given number of instructions.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    // Junk that gets sprinkled between every instruction, including a few letters to make things confusing.
+    const NOISE: &[char] = &[
+        '!', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '/', ':', ';', '<', '>', '?', '@', '[', ']', '^',
+        '_', '{', '}', ' ', 'd', 'h', 'l', 'm', 'n', 'o', 't', 'u', 'w', 'x',
+    ];
+
+    let mut output = String::with_capacity(size * 16);
+
+    for _ in 0 .. size {
+        for _ in 0 .. random.below(8) {
+            output.extend(random.choose(NOISE));
+        }
+
+        let lhs = random.between(1, 999);
+        let rhs = random.between(1, 999);
+
+        let instruction = match random.below(10) {
+            0 => "do()".to_string(),
+            1 => "don't()".to_string(),
+            // Near-misses that should never be counted.
+            2 => format!("mul({lhs}, {rhs})"),
+            3 => format!("mul[{lhs},{rhs}]"),
+            _ => format!("mul({lhs},{rhs})"),
+        };
+
+        output.push_str(&instruction);
+    }
+
+    output.push('\n');
+    output
+}
+
+/// Returns how likely it is that the input is a chunk of corrupted memory.
+pub fn identify(string: &str) -> f64 {
+    // There's not much structure to corruption, so we just look for the instructions themselves.
+    match (string.contains("mul("), string.contains("do()") || string.contains("don't()")) {
+        (true, true) => 1.0,
+        (true, false) => 0.9,
+        (false, true) => 0.3,
+        (false, false) => 0.0,
+    }
 }
 
 /// Advances the given iterator by the length of the given string, ensuring that the returned characters match the
@@ -119,8 +165,8 @@ where
     Ok(digits.parse()?)
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    let string = self::input(path)?;
+pub fn solution_1(string: &str) -> SolutionResult {
+    let string = self::input(string)?;
 
     let mut total = 0;
 
@@ -139,8 +185,8 @@ pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
     Ok(total)
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
-    let string = self::input(path)?;
+pub fn solution_2(string: &str) -> SolutionResult {
+    let string = self::input(string)?;
 
     let mut last_enable = None;
     let mut total = 0;
@@ -151,15 +197,22 @@ pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
         match character {
             'd' => {
                 if let Ok(enable) = string[index ..].parse::<Enable>() {
-                    last_enable = Some(enable);
+                    // We keep the index around purely so that tracing can point at it.
+                    last_enable = Some((enable, index));
                 }
             }
             // Holy indentation Batman!
             'm' => {
                 if let Ok(multiply) = string[index ..].parse::<Multiply>() {
                     // If it hasn't been set initially we can just assume that the operations are fine to use.
-                    if last_enable.is_none_or(|v| v == Enable::Do) {
-                        total += multiply.get();
+                    match last_enable {
+                        None | Some((Enable::Do, _)) => total += multiply.get(),
+                        Some((Enable::Dont, dont_index)) => trace!(
+                            "skipped_multiply",
+                            "mul({},{}) at byte {index} skipped by don't() at byte {dont_index}",
+                            multiply.0,
+                            multiply.1
+                        ),
                     }
                 }
             }
@@ -169,3 +222,41 @@ pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
 
     Ok(total)
 }
+
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+/// Returns the product of the `mul(X,Y)` instruction at the very start of the given string, if there is one.
+///
+/// As per the puzzle, both numbers must have between one and three digits.
+fn reference_multiply(string: &str) -> Option<u64> {
+    let (lhs, rest) = string.strip_prefix("mul(")?.split_once(',')?;
+    let (rhs, _) = rest.split_once(')')?;
+    let is_valid = |v: &str| (1 ..= 3).contains(&v.len()) && v.bytes().all(|b| b.is_ascii_digit());
+
+    (is_valid(lhs) && is_valid(rhs)).then(|| lhs.parse::<u64>().unwrap() * rhs.parse::<u64>().unwrap())
+}
+
+fn reference_1(string: &str) -> SolutionResult {
+    Ok((0 .. string.len()).filter_map(|index| string.get(index ..).and_then(self::reference_multiply)).sum())
+}
+
+fn reference_2(string: &str) -> SolutionResult {
+    let mut enabled = true;
+    let mut total = 0;
+
+    for rest in (0 .. string.len()).filter_map(|index| string.get(index ..)) {
+        if rest.starts_with("do()") {
+            enabled = true;
+        } else if rest.starts_with("don't()") {
+            enabled = false;
+        } else if enabled {
+            total += self::reference_multiply(rest).unwrap_or(0);
+        }
+    }
+
+    Ok(total)
+}
+
+/// This day doesn't have any commands of its own within the REPL.
+pub const REPL: Option<Open> = None;
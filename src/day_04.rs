@@ -1,70 +1,115 @@
-use std::collections::BTreeMap;
-use std::path::Path;
-
 use anyhow::Result;
 
-use crate::SolutionResult;
-use crate::utility::grid::{Grid2D, Offset2D, Pos2D, read_to_char_grid};
+use crate::identify::grid_ratio;
+use crate::repl::Open;
+use crate::utility::grid::{Direction, Grid2D, GridPattern, Orientation, Pos2D, Size2D, string_to_char_grid};
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 // Split the input string into a grid of characters.
 type Input = Grid2D<char>;
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    read_to_char_grid(path, |_, c| Some(c))
+fn input(string: &str) -> Result<Input> {
+    string_to_char_grid(string, |_, c| Some(c))
 }
 
-// Recursive search algorithm to look for characters in a given direction until the stack is empty or the position
-// goes out of bounds.
-fn search_direction(grid: &Grid2D<char>, pos: Pos2D, offset: Offset2D, stack: &[char], index: usize) -> bool {
-    stack.get(index).is_none_or(|c| {
-        let Some(pos) = pos.offset(offset) else { return false };
-
-        grid.get(pos).is_some_and(|v| v == c) && search_direction(grid, pos, offset, stack, index + 1)
-    })
-}
+/// Generates a random square word search with the given width.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    const LETTERS: &[char] = &['X', 'M', 'A', 'S'];
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    const PATTERN: &[char] = &['X', 'M', 'A', 'S'];
+    let size = size.max(1);
+    let bounds = Size2D::try_new(size, size).expect("the size is at least 1");
+    let mut grid: Vec<Vec<char>> =
+        (0 .. size).map(|_| (0 .. size).flat_map(|_| random.choose(LETTERS)).copied().collect()).collect();
 
-    let grid = self::input(path)?;
-    let mut count = 0;
+    // Pure noise rarely spells anything, so we also plant some real words in random directions.
+    for _ in 0 .. size * size / 8 {
+        let pos = Pos2D::new(random.below(size as u64) as usize, random.below(size as u64) as usize);
+        let Some(direction) = random.choose(&Direction::ALL).copied() else { continue };
 
-    for (pos, option) in grid.iter() {
-        let Some(character) = option else { continue };
+        for (index, letter) in LETTERS.iter().enumerate() {
+            let offset = direction.offset().multiply(index as isize);
+            let Some(pos) = pos.offset_within(offset, bounds) else { break };
 
-        // We need to manually check the first character, since we don't know the direction yet.
-        if PATTERN.first().is_some_and(|v| v == character) {
-            // Check in every direction from the target position.
-            count += Offset2D::directions().filter(|d| self::search_direction(&grid, pos, *d, PATTERN, 1)).count();
+            grid[pos.y()][pos.x()] = *letter;
         }
     }
 
-    Ok(count as _)
+    grid.into_iter().flat_map(|row| row.into_iter().chain(['\n'])).collect()
+}
+
+/// Returns how likely it is that the input is a word search.
+pub fn identify(string: &str) -> f64 {
+    grid_ratio(string, |c| matches!(c, 'X' | 'M' | 'A' | 'S'))
+}
+
+/// The word that part 1 looks for, running from left to right. Every other straight direction is just a rotation or
+/// reflection of it.
+const WORD: &str = "XMAS";
+/// The same word running diagonally, since no amount of rotating the straight one will ever tilt it.
+const DIAGONAL_WORD: &str = "X...\n.M..\n..A.\n...S";
+/// The two crossed `MAS`s that part 2 looks for, which can face any way.
+const CROSS: &str = "M.S\n.A.\nM.S";
+
+/// Counts how many times the given template appears within the grid, in any orientation.
+fn count_pattern(grid: &Grid2D<char>, template: &str) -> Result<usize> {
+    let pattern: GridPattern<char> = template.parse()?;
+
+    Ok(grid.find_pattern(&pattern, &Orientation::ALL).count())
+}
+
+pub fn solution_1(string: &str) -> SolutionResult {
+    let grid = self::input(string)?;
+
+    Ok((self::count_pattern(&grid, WORD)? + self::count_pattern(&grid, DIAGONAL_WORD)?) as _)
+}
+
+pub fn solution_2(string: &str) -> SolutionResult {
+    let grid = self::input(string)?;
+
+    Ok(self::count_pattern(&grid, CROSS)? as _)
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
-    const PATTERN: &[char] = &['M', 'A', 'S'];
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+fn reference_1(string: &str) -> SolutionResult {
+    const WORD: [char; 4] = ['X', 'M', 'A', 'S'];
 
-    let grid = self::input(path)?;
-    let mut centerpoints = BTreeMap::<Pos2D, usize>::new();
+    let grid = self::input(string)?;
+    let mut count = 0;
 
-    for (pos, option) in grid.iter() {
-        let Some(character) = option else { continue };
+    for pos in grid.size().positions() {
+        for direction in Direction::ALL {
+            let offset = direction.offset();
 
-        // We need to manually check the first character, since we don't know the direction yet.
-        if PATTERN.first().is_none_or(|v| v != character) {
-            continue;
+            if (0 .. WORD.len()).all(|i| {
+                pos.offset(offset.multiply(i as isize)).and_then(|v| grid.get(v)).is_some_and(|v| *v == WORD[i])
+            }) {
+                count += 1;
+            }
         }
+    }
+
+    Ok(count)
+}
 
-        for offset in Offset2D::directions().filter(|d| {
-            // Only allow diagonal directions, and only run the body for successful searches.
-            (d.x() != 0 && d.y() != 0) && self::search_direction(&grid, pos, *d, PATTERN, 1)
-        }) {
-            let Some(pos) = pos.offset(offset) else { continue };
+fn reference_2(string: &str) -> SolutionResult {
+    let grid = self::input(string)?;
+    let get = |pos: Pos2D, x: isize, y: isize| pos.offset_by(x, y).and_then(|v| grid.get(v)).copied();
+    let mut count = 0;
+
+    for pos in grid.size().positions().filter(|v| grid.get(*v) == Some(&'A')) {
+        // Both diagonals need to have an 'M' on one end and an 'S' on the other.
+        let diagonals = [(get(pos, -1, -1), get(pos, 1, 1)), (get(pos, 1, -1), get(pos, -1, 1))];
 
-            *centerpoints.entry(pos).or_default() += 1;
+        if diagonals.iter().all(|v| matches!(v, (Some('M'), Some('S')) | (Some('S'), Some('M')))) {
+            count += 1;
         }
     }
 
-    Ok(centerpoints.into_values().filter(|v| *v > 1).count() as _)
+    Ok(count)
 }
+
+/// This day doesn't have any commands of its own within the REPL.
+pub const REPL: Option<Open> = None;
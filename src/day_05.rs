@@ -1,12 +1,15 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::fmt::Write;
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result};
 
-use crate::SolutionResult;
+use crate::error::Error;
+use crate::identify::{self, line_ratio};
+use crate::repl::{Open, Session};
+use crate::trace::{self, trace};
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 type Input = Manual;
 
@@ -16,70 +19,107 @@ struct Manual {
     updates: Box<[Box<[u8]>]>,
 }
 
-struct ManualIndex<'m> {
-    inner: &'m Manual,
+struct ManualIndex {
+    inner: Manual,
     // Keeps track of every number and the values that are allowed to be below or above it.
     cache: HashMap<u8, (HashSet<u8>, HashSet<u8>)>,
 }
 
-impl<'m> ManualIndex<'m> {
-    pub fn new(manual: &'m Manual) -> Self {
-        Self { inner: manual, cache: HashMap::new() }
-    }
+impl ManualIndex {
+    /// Indexes the given manual's rules, so that pages can be sorted without going through all of them every time.
+    pub fn new(manual: Manual) -> Self {
+        let mut cache: HashMap<u8, (HashSet<u8>, HashSet<u8>)> = HashMap::new();
 
-    pub fn setup(&mut self) {
-        for (lhs, rhs) in self.inner.rules.iter().copied() {
-            self.cache.entry(lhs).or_default().1.insert(rhs);
-            self.cache.entry(rhs).or_default().0.insert(lhs);
+        for (lhs, rhs) in manual.rules.iter().copied() {
+            cache.entry(lhs).or_default().1.insert(rhs);
+            cache.entry(rhs).or_default().0.insert(lhs);
         }
+
+        Self { inner: manual, cache }
     }
 
     /// Sorts two values according to the inner manual's paging rules.
-    pub fn sort(&self, lhs: &u8, rhs: &u8) -> Option<Ordering> {
-        assert!(!self.cache.is_empty(), "make sure you run `.setup` stupid");
+    ///
+    /// Pages that no rule mentions (which is every page, if there are no rules at all) can go anywhere, so they're
+    /// treated as equal to everything.
+    pub fn sort(&self, lhs: &u8, rhs: &u8) -> Ordering {
+        let Some((lesser, greater)) = self.cache.get(lhs) else { return Ordering::Equal };
 
-        let (lesser, greater) = self.cache.get(lhs)?;
-
-        Some(if lesser.contains(rhs) {
+        if lesser.contains(rhs) {
             Ordering::Greater
         } else if greater.contains(rhs) {
             Ordering::Less
         } else {
             Ordering::Equal
+        }
+    }
+
+    /// Returns the first rule that the given update breaks, if any.
+    pub fn violation(&self, update: &[u8]) -> Option<(u8, u8)> {
+        update.iter().enumerate().find_map(|(index, lhs)| {
+            // If the later value sorts before the current one, there must be a rule saying so.
+            update[index + 1 ..].iter().find(|rhs| self.sort(lhs, rhs).is_gt()).map(|rhs| (*rhs, *lhs))
         })
     }
 
+    /// Traces every update that breaks one of the manual's rules.
+    pub fn trace_violations(&self) {
+        if !trace::is_enabled() {
+            return;
+        }
+
+        for (index, update) in self.inner.updates.iter().enumerate() {
+            if let Some((lhs, rhs)) = self.violation(update) {
+                trace!("rule_violation", "update {index} violates rule {lhs}|{rhs}");
+            }
+        }
+    }
+
     /// Returns an iterator over the already sorted updates.
     pub fn sorted(&self) -> impl Iterator<Item = &[u8]> {
-        self.inner.updates.iter().filter_map(|v| {
-            // We want the arrays to be sorted, ascending, in accordance with the manual's rules.
-            v.is_sorted_by(|a, b| self.sort(a, b).is_some_and(|v| v.is_le())).then_some(&**v)
-        })
+        // Pages that no rule mentions sit between the others, so every pair has to be checked, not just neighbours.
+        self.inner.updates.iter().filter(|v| self.violation(v).is_none()).map(|v| &**v)
     }
 
-    /// Returns an iterator that actively sorts the updates.
-    pub fn sorting(&self) -> impl Iterator<Item = Box<[u8]>> {
-        self.inner.updates.iter().map(|v| {
-            let mut array = Box::<[u8]>::from(&**v);
+    /// Returns an iterator that actively sorts the updates that aren't sorted already.
+    pub fn sorting(&self) -> impl Iterator<Item = Result<Box<[u8]>>> {
+        self.inner.updates.iter().filter(|v| self.violation(v).is_some()).map(|v| self.order(v))
+    }
 
-            // We can safely assume any unexpected values should be treated as equals.
-            // Y'know, we could learn a lot from these bytes.
-            array.sort_unstable_by(|a, b| self.sort(a, b).unwrap_or(Ordering::Equal));
+    /// Orders the given update according to the manual's rules.
+    ///
+    /// Only pages that a rule ties to another page of the update get moved around (within the slots they already took
+    /// up), so anything else stays right where it was. Rules that go around in a cycle can't be followed by any order,
+    /// so they're reported as a parse error.
+    pub fn order(&self, update: &[u8]) -> Result<Box<[u8]>> {
+        let slots: Vec<usize> =
+            (0 .. update.len()).filter(|&i| update.iter().any(|v| self.sort(&update[i], v).is_ne())).collect();
+        let mut pending: Vec<u8> = slots.iter().map(|&i| update[i]).collect();
+        let mut array = Box::<[u8]>::from(update);
 
-            array
-        })
+        for slot in slots {
+            // The earliest page that doesn't have to come after any of the others goes next.
+            let Some(next) = pending.iter().position(|lhs| pending.iter().all(|rhs| !self.sort(lhs, rhs).is_gt()))
+            else {
+                let pages = pending.iter().map(u8::to_string).collect::<Vec<_>>().join(", ");
+
+                return Err(Error::parse(format!("the rules between pages {pages} form a cycle")).into());
+            };
+
+            array[slot] = pending.remove(next);
+        }
+
+        Ok(array)
     }
 }
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
+fn input(string: &str) -> Result<Input> {
     let mut rules = Vec::new();
     let mut updates = Vec::new();
     // Track whether we've met the separating line.
     let mut finished_rules = false;
 
-    for result in BufReader::new(File::open(path)?).lines() {
-        let line = result?;
-
+    for (index, line) in string.lines().enumerate() {
         if line.is_empty() {
             finished_rules = true;
 
@@ -90,27 +130,81 @@ fn input(path: impl AsRef<Path>) -> Result<Input> {
             let mut list = Vec::new();
 
             for number in line.split(',') {
-                list.push(number.parse()?);
+                list.push(number.parse().map_err(|v| Error::parse_within(index + 1, line, number, v))?);
             }
 
             updates.push(list.into_boxed_slice());
         } else {
             let Some((lhs, rhs)) = line.split_once('|') else {
-                bail!("invalid rule format");
+                return Err(Error::parse_at(index + 1, "invalid rule format").into());
             };
 
-            rules.push((lhs.parse()?, rhs.parse()?));
+            let parse = |number: &str| number.parse().map_err(|v| Error::parse_within(index + 1, line, number, v));
+
+            rules.push((parse(lhs)?, parse(rhs)?));
         }
     }
 
     Ok(Manual { rules: rules.into_boxed_slice(), updates: updates.into_boxed_slice() })
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    let manual = self::input(path)?;
-    let mut index = ManualIndex::new(&manual);
+/// Generates a random list of page ordering rules followed by the given number of updates.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    // Every page is a two-digit number, and we pick a random total ordering so that the rules never contradict.
+    let mut pages: Vec<u64> = (10 ..= 99).collect();
+
+    random.shuffle(&mut pages);
+    pages.truncate(size.clamp(5, pages.len()));
+
+    let mut rules = Vec::with_capacity(pages.len() * pages.len() / 2);
+
+    for (index, lhs) in pages.iter().enumerate() {
+        for rhs in &pages[index + 1 ..] {
+            rules.push(format!("{lhs}|{rhs}"));
+        }
+    }
+
+    random.shuffle(&mut rules);
+
+    let mut output = rules.join("\n");
+
+    output.push_str("\n\n");
+
+    for _ in 0 .. size {
+        // Updates always have an odd length so that they have a middle page.
+        let length = random.between(1, (pages.len().min(23) as u64 - 1) / 2) as usize * 2 + 1;
+        let mut update = pages.clone();
+
+        random.shuffle(&mut update);
+        update.truncate(length);
+
+        // About half of the updates should already be correctly ordered.
+        if random.chance(1, 2) {
+            update.sort_unstable_by_key(|v| pages.iter().position(|p| p == v));
+        }
+
+        _ = writeln!(output, "{}", update.iter().map(u64::to_string).collect::<Vec<_>>().join(","));
+    }
+
+    output
+}
+
+/// Returns how likely it is that the input is a list of page ordering rules followed by a list of updates.
+pub fn identify(string: &str) -> f64 {
+    let (rules, updates) = string.split_once("\n\n").unwrap_or((string, ""));
+
+    let rule_ratio = line_ratio(rules, |v| {
+        v.split_once('|').is_some_and(|(a, b)| identify::is_integer_list(a, " ") && identify::is_integer_list(b, " "))
+    });
+    let update_ratio = line_ratio(updates, |v| identify::is_integer_list(v, ","));
+
+    (rule_ratio + update_ratio) / 2.0
+}
+
+pub fn solution_1(string: &str) -> SolutionResult {
+    let index = ManualIndex::new(self::input(string)?);
 
-    index.setup();
+    index.trace_violations();
 
     Ok(index
         .sorted()
@@ -121,21 +215,175 @@ pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
         .sum::<u64>() as _)
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
-    let manual = self::input(path)?;
-    let mut index = ManualIndex::new(&manual);
+pub fn solution_2(string: &str) -> SolutionResult {
+    let index = ManualIndex::new(self::input(string)?);
 
-    index.setup();
+    index.trace_violations();
 
-    Ok(index
+    index
         .sorting()
-        .filter(|v| {
-            // Only allow values that have not yet been sorted.
-            !index.sorted().any(|v2| &**v == v2)
-        })
         .map(|v| {
+            let v = v?;
+
             // Grab the middle-most value and up-cast it.
-            v[v.len() / 2] as u64
+            Ok(v[v.len() / 2] as u64)
         })
-        .sum::<u64>() as _)
+        .sum()
+}
+
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+/// Returns the positions of the first two pages of the update that break one of the manual's rules.
+fn reference_violation(manual: &Manual, update: &[u8]) -> Option<(usize, usize)> {
+    manual.rules.iter().find_map(|(lhs, rhs)| {
+        let lhs_index = update.iter().position(|v| v == lhs)?;
+        let rhs_index = update.iter().position(|v| v == rhs)?;
+
+        (lhs_index > rhs_index).then_some((lhs_index, rhs_index))
+    })
+}
+
+fn reference_1(string: &str) -> SolutionResult {
+    let manual = self::input(string)?;
+
+    Ok(manual
+        .updates
+        .iter()
+        .filter(|v| self::reference_violation(&manual, v).is_none())
+        .map(|v| v[v.len() / 2] as u64)
+        .sum())
+}
+
+fn reference_2(string: &str) -> SolutionResult {
+    let manual = self::input(string)?;
+    let mut total = 0;
+
+    for update in manual.updates.iter().filter(|v| self::reference_violation(&manual, v).is_some()) {
+        let mut update = update.to_vec();
+
+        // Keep swapping misplaced pages until every rule is satisfied.
+        while let Some((lhs_index, rhs_index)) = self::reference_violation(&manual, &update) {
+            update.swap(lhs_index, rhs_index);
+        }
+
+        total += update[update.len() / 2] as u64;
+    }
+
+    Ok(total)
+}
+
+/// Opens the manual within the REPL.
+///
+/// The rules never change during a session, so they're only indexed the once.
+pub const REPL: Option<Open> = Some(|string| Ok(Box::new(ManualIndex::new(self::input(string)?))));
+
+impl Session for ManualIndex {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("rules <page>", "lists the pages that must come before and after the given page"),
+            ("check <page>,<page>,...", "checks whether an update is in order, fixing it if it isn't"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, arguments: &str) -> Option<Result<String>> {
+        Some(match command {
+            "rules" => self::repl_rules(self, arguments),
+            "check" => self::repl_check(self, arguments),
+            _ => return None,
+        })
+    }
+}
+
+/// Lists the pages that must come before and after the given page.
+fn repl_rules(index: &ManualIndex, arguments: &str) -> Result<String> {
+    let page: u8 = arguments.trim().parse().context("expected a page number")?;
+    let Some((before, after)) = index.cache.get(&page) else { return Ok(format!("no rules mention page {page}")) };
+
+    let list = |pages: &HashSet<u8>| {
+        let mut pages: Vec<_> = pages.iter().copied().collect();
+
+        pages.sort_unstable();
+        pages.iter().map(u8::to_string).collect::<Vec<_>>().join(", ")
+    };
+
+    Ok(format!("must come after:  {}\nmust come before: {}", list(before), list(after)))
+}
+
+/// Checks whether the given update is in order, fixing it if it isn't.
+fn repl_check(index: &ManualIndex, arguments: &str) -> Result<String> {
+    let update: Vec<u8> =
+        arguments.split(',').map(|v| v.trim().parse()).collect::<Result<_, _>>().context("expected a list of pages")?;
+
+    let Some((lhs, rhs)) = index.violation(&update) else {
+        return Ok(format!("in order, and the middle page is {}", update[update.len() / 2]));
+    };
+
+    let fixed = index.order(&update)?;
+    let middle = fixed[fixed.len() / 2];
+    let fixed = fixed.iter().map(u8::to_string).collect::<Vec<_>>().join(",");
+
+    Ok(format!("out of order, since it breaks rule {lhs}|{rhs}\nfixed: {fixed}, and the middle page is {middle}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unmentioned_pages_dont_hide_violations() {
+        // The page in the middle has no rules, which used to make this update look sorted.
+        let input = "27|91\n\n91,2,27\n";
+
+        assert_eq!(self::solution_1(input).unwrap(), 0);
+        assert_eq!(self::solution_2(input).unwrap(), 2);
+        assert_eq!(self::reference_2(input).unwrap(), 2);
+    }
+
+    #[test]
+    fn cyclic_rules_are_an_error() {
+        // Updates this long would make the standard library's sort panic over the inconsistent ordering.
+        let update = (10 ..= 40).map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+        let input = format!("10|11\n11|12\n12|10\n\n{update}\n");
+        let error = self::solution_2(&input).unwrap_err();
+
+        assert!(matches!(Error::find(&error), Some(Error::Parse(..))), "{error}");
+    }
+
+    /// Runs a REPL command against a session opened with the given input.
+    fn repl(input: &str, command: &str, arguments: &str) -> Result<String> {
+        let mut session = REPL.unwrap()(input)?;
+
+        session.run(command, arguments).expect("the command should exist")
+    }
+
+    #[test]
+    fn repl_lists_rules() {
+        let input = "47|53\n97|47\n47|13\n\n97,47,53\n";
+
+        assert_eq!(self::repl(input, "rules", "47").unwrap(), "must come after:  97\nmust come before: 13, 53");
+        assert_eq!(self::repl(input, "rules", " 14 ").unwrap(), "no rules mention page 14");
+        assert!(self::repl(input, "rules", "page").is_err());
+    }
+
+    #[test]
+    fn repl_checks_updates() {
+        let input = "47|53\n97|47\n47|13\n\n97,47,53\n";
+
+        assert_eq!(self::repl(input, "check", "97, 47, 53").unwrap(), "in order, and the middle page is 47");
+        assert_eq!(
+            self::repl(input, "check", "53,2,47,97,8").unwrap(),
+            "out of order, since it breaks rule 47|53\nfixed: 97,2,47,53,8, and the middle page is 47"
+        );
+        assert!(self::repl(input, "check", "53,x").is_err());
+        assert!(REPL.unwrap()(input).unwrap().run("sort", "").is_none());
+    }
+
+    #[test]
+    fn repl_reports_cyclic_rules() {
+        let update = (10 ..= 40).rev().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+        let error = self::repl("10|11\n11|12\n12|10\n\n10,11\n", "check", &update).unwrap_err();
+
+        assert_eq!(error.to_string(), "invalid input: the rules between pages 12, 11, 10 form a cycle");
+    }
 }
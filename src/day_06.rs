@@ -1,105 +1,166 @@
-use std::path::Path;
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 
-use crate::SolutionResult;
-use crate::utility::grid::{Grid2D, Offset2D, Pos2D, read_to_char_grid};
+use crate::error::Error;
+use crate::identify::grid_ratio;
+use crate::repl::{Open, Session};
+use crate::trace::trace;
+use crate::utility::grid::{Direction, Grid2D, Pos2D, Size2D, Tile, string_to_tile_grid};
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
-type Input = (Grid2D<char>, Pos2D);
+/// Everything that can be found on the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MapTile {
+    Floor,
+    Wall,
+    Guard(Direction),
+    /// Floor that the guard has already walked over.
+    Visited,
+    /// The extra obstruction that part 2 tries placing everywhere.
+    Obstruction,
+}
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    let mut guard_pos = None;
+impl MapTile {
+    /// Returns whether the guard has to turn rather than walk onto this tile.
+    const fn is_blocking(&self) -> bool {
+        match self {
+            Self::Wall | Self::Obstruction => true,
+            Self::Floor | Self::Guard(_) | Self::Visited => false,
+        }
+    }
+}
 
-    let grid = read_to_char_grid(path, |pos, c| match c {
-        '#' => Some('#'),
-        // We ignore the guard character because we don't care about people, only octothorpes.
-        '^' => {
-            guard_pos = Some(pos);
+impl Tile for MapTile {
+    fn from_char(character: char) -> Option<Self> {
+        match character {
+            '.' => Some(Self::Floor),
+            '#' => Some(Self::Wall),
+            // Only arrows count, since the guard doesn't answer to compass letters.
+            '^' | '>' | 'v' | '<' => Direction::from_char(character).map(Self::Guard),
+            // Visited tiles and obstructions only ever show up while solving, so they're never part of an input.
+            _ => None,
+        }
+    }
 
-            None
+    fn to_char(&self) -> char {
+        match self {
+            Self::Floor => '.',
+            Self::Wall => '#',
+            Self::Guard(direction) => direction.to_char(),
+            Self::Visited => 'X',
+            Self::Obstruction => 'O',
         }
-        // We also ignore every other character, because again, we only care about octothorpes.
-        _ => None,
-    })?;
+    }
+}
 
-    let Some(guard_pos) = guard_pos else { bail!("missing initial guard position") };
+/// The map, along with where the guard starts and which way they're facing.
+type Input = (Grid2D<MapTile>, Pos2D, Direction);
 
-    Ok((grid, guard_pos))
-}
+fn input(string: &str) -> Result<Input> {
+    let mut grid = string_to_tile_grid::<MapTile>(string)?;
+    let mut guard = None;
+
+    for (pos, tile) in grid.iter_mut() {
+        // The guard walks around rather than being part of the map, so they're swapped out for the floor below them.
+        if let Some(MapTile::Guard(direction)) = *tile {
+            guard = Some((pos, direction));
+            *tile = Some(MapTile::Floor);
+        }
+    }
 
-const fn turn(direction: u8) -> u8 {
-    direction.wrapping_add(1) % 4
+    let Some((guard_pos, direction)) = guard else {
+        return Err(Error::parse("missing initial guard position").into());
+    };
+
+    Ok((grid, guard_pos, direction))
 }
 
-const fn direction_to_offset(direction: u8) -> Offset2D {
-    match direction % 4 {
-        0 => Offset2D::new(0, -1),
-        1 => Offset2D::new(1, 0),
-        2 => Offset2D::new(0, 1),
-        3 => Offset2D::new(-1, 0),
-        _ => unreachable!(),
+/// Generates a random square map with the given width, containing a single guard.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    let size = size.max(1);
+    let guard = Pos2D::new(random.below(size as u64) as usize, random.below(size as u64) as usize);
+    let mut output = String::with_capacity(size * (size + 1));
+
+    for pos in Size2D::square(NonZeroUsize::new(size).unwrap()).positions() {
+        output.push(match pos {
+            _ if pos == guard => '^',
+            _ if random.chance(1, 10) => '#',
+            _ => '.',
+        });
+
+        if pos.x() == size - 1 {
+            output.push('\n');
+        }
     }
+
+    output
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    let (mut grid, mut guard_pos) = self::input(path)?;
-    let mut direction = 0;
+/// Returns how likely it is that the input is a map containing a guard.
+pub fn identify(string: &str) -> f64 {
+    let ratio = grid_ratio(string, |c| matches!(c, '.' | '#' | '^'));
 
-    // We can just subtract the added characters from the initial cell count to get the touched positions.
-    let initial_tiles = grid.cells().filter(|v| v.is_some()).count();
+    // The guard is the real giveaway here.
+    if string.matches('^').count() == 1 { ratio } else { ratio * 0.5 }
+}
 
-    while grid.size().contains_position(guard_pos) {
-        let offset = self::direction_to_offset(direction);
-        let Some(next_pos) = guard_pos.offset(offset) else { break };
+pub fn solution_1(string: &str) -> SolutionResult {
+    let (mut grid, mut guard_pos, mut direction) = self::input(string)?;
+    let mut states = HashSet::new();
 
-        match grid.get(next_pos) {
-            Some('#') => direction = self::turn(direction),
-            // Place an X over all traveled tiles.
-            Some('X') | None => {
-                grid.set(guard_pos, 'X');
-                guard_pos = next_pos;
-            }
-            Some(c) => bail!("unexpected character {c:?}"),
+    loop {
+        // The puzzle promises that the guard leaves eventually, but a map that traps them would otherwise never finish.
+        if !states.insert((guard_pos, direction)) {
+            bail!("the guard is stuck in a loop and never leaves the map");
         }
-    }
 
-    let final_tiles = grid.cells().filter(|v| v.is_some()).count();
+        // Mark every tile the guard stands on, so that all that's left at the end is counting them.
+        grid.set(guard_pos, MapTile::Visited);
+
+        let Some(next_pos) = guard_pos.step_within(direction, grid.size()) else { break };
+
+        if grid.get(next_pos).is_some_and(MapTile::is_blocking) {
+            direction = direction.turn_right();
+
+            trace!("turn", "guard turned at ({}, {}) to face {direction}", guard_pos.x(), guard_pos.y());
+        } else {
+            guard_pos = next_pos;
+        }
+    }
 
-    Ok((final_tiles - initial_tiles) as _)
+    Ok(grid.cells().filter(|v| **v == Some(MapTile::Visited)).count() as _)
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
-    let (grid, guard_start_pos) = self::input(path)?;
+pub fn solution_2(string: &str) -> SolutionResult {
+    let (grid, guard_start_pos, guard_start_direction) = self::input(string)?;
     let mut count: usize = 0;
+    let floor = grid.iter().filter_map(|(p, v)| (*v == Some(MapTile::Floor) && p != guard_start_pos).then_some(p));
 
     // And so begins the slowest implementation so far.
-    'outer: for obstacle_pos in grid.iter().filter_map(|(p, v)| (v.is_none() && p != guard_start_pos).then_some(p)) {
-        let mut snapshots = Vec::<(u8, Pos2D)>::new();
-        let mut direction = 0;
+    'outer: for obstacle_pos in floor {
+        let mut snapshots = Vec::<(Direction, Pos2D)>::new();
+        let mut direction = guard_start_direction;
         let mut guard_pos = guard_start_pos;
         let mut grid = grid.clone();
 
-        grid.set(obstacle_pos, 'O');
+        grid.set(obstacle_pos, MapTile::Obstruction);
 
-        'inner: while grid.size().contains_position(guard_pos) {
-            let mut new_char = if direction % 2 == 0 { '|' } else { '-' };
-
-            // Assume any passed characters will be ours. Our greed is immeasurable.
-            if grid.get(guard_pos).is_some_and(|c| *c != new_char) {
-                new_char = '+';
-            }
+        'inner: loop {
+            // Check for bounds.
+            let Some(next_pos) = guard_pos.step_within(direction, grid.size()) else { continue 'outer };
 
-            grid.set(guard_pos, new_char);
+            if grid.get(next_pos).is_some_and(MapTile::is_blocking) {
+                direction = direction.turn_right();
 
-            // Check for bounds.
-            let offset = self::direction_to_offset(direction);
-            let Some(next_pos) = guard_pos.offset(offset) else { continue 'outer };
-            if !grid.size().contains_position(next_pos) {
-                continue 'outer;
-            }
+                // A guard that's boxed in just spins on the spot forever, which is still a loop.
+                if snapshots.contains(&(direction, guard_pos)) {
+                    break 'inner;
+                }
 
-            if let Some('#' | 'O') = grid.get(next_pos) {
-                direction = self::turn(direction);
+                snapshots.push((direction, guard_pos));
 
                 continue 'inner;
             }
@@ -114,8 +175,206 @@ pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
             }
         }
 
+        trace!("loop", "obstacle at ({}, {}) traps the guard in a loop", obstacle_pos.x(), obstacle_pos.y());
+
         count += 1;
     }
 
     Ok(count as _)
 }
+
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+/// Walks the guard around the grid, returning every visited position, or `None` if the guard gets stuck in a loop.
+fn reference_walk(grid: &Grid2D<MapTile>, start: Pos2D, facing: Direction) -> Option<HashSet<Pos2D>> {
+    let mut direction = facing;
+    let mut pos = start;
+    let mut seen = HashSet::new();
+
+    while seen.insert((pos, direction)) {
+        let Some(next_pos) = pos.step_within(direction, grid.size()) else {
+            return Some(seen.into_iter().map(|(pos, _)| pos).collect());
+        };
+
+        if grid.get(next_pos).is_some_and(MapTile::is_blocking) {
+            direction = direction.turn_right();
+        } else {
+            pos = next_pos;
+        }
+    }
+
+    None
+}
+
+fn reference_1(string: &str) -> SolutionResult {
+    let (grid, guard_pos, direction) = self::input(string)?;
+
+    Ok(self::reference_walk(&grid, guard_pos, direction).map_or(0, |v| v.len()) as _)
+}
+
+fn reference_2(string: &str) -> SolutionResult {
+    let (grid, guard_pos, direction) = self::input(string)?;
+    let mut count = 0;
+
+    for obstacle_pos in grid.size().positions().filter(|v| *v != guard_pos && grid.get(*v) == Some(&MapTile::Floor)) {
+        let mut grid = grid.clone();
+
+        grid.set(obstacle_pos, MapTile::Obstruction);
+
+        if self::reference_walk(&grid, guard_pos, direction).is_none() {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Opens the map within the REPL, with the guard at its starting position.
+pub const REPL: Option<Open> = Some(|string| {
+    let (grid, start, facing) = self::input(string)?;
+
+    Ok(Box::new(Patrol {
+        grid,
+        start,
+        facing,
+        guard: Some(start),
+        direction: facing,
+        visited: HashSet::from([start]),
+        steps: 0,
+    }))
+});
+
+/// A guard that's being walked around the map one step at a time.
+struct Patrol {
+    grid: Grid2D<MapTile>,
+    start: Pos2D,
+    /// The direction that the guard starts off facing.
+    facing: Direction,
+    /// The guard's current position, which is `None` once it has left the map.
+    guard: Option<Pos2D>,
+    direction: Direction,
+    visited: HashSet<Pos2D>,
+    steps: usize,
+}
+
+impl Patrol {
+    /// Returns a short description of where the guard currently is.
+    fn status(&self) -> String {
+        match self.guard {
+            Some(pos) => format!(
+                "after {} step(s), the guard is at ({}, {}) facing {}, having visited {} position(s)",
+                self.steps,
+                pos.x(),
+                pos.y(),
+                self.direction.to_char(),
+                self.visited.len()
+            ),
+            None => format!(
+                "after {} step(s), the guard has left the map, having visited {} position(s)",
+                self.steps,
+                self.visited.len()
+            ),
+        }
+    }
+}
+
+impl Session for Patrol {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("show-grid", "draws the map, marking the guard and every position it has visited"),
+            ("step [<N>]", "moves the guard forward N times (once by default), turning whenever it's blocked"),
+            ("reset", "puts the guard back at its starting position"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, arguments: &str) -> Option<Result<String>> {
+        Some(match command {
+            "show-grid" => Ok(self::repl_show_grid(self)),
+            "step" => self::repl_step(self, arguments),
+            "reset" => {
+                self.guard = Some(self.start);
+                self.direction = self.facing;
+                self.visited = HashSet::from([self.start]);
+                self.steps = 0;
+
+                Ok(self.status())
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Draws the map, marking the guard and every position it has visited.
+fn repl_show_grid(patrol: &Patrol) -> String {
+    let mut grid = patrol.grid.clone();
+
+    for pos in &patrol.visited {
+        grid.set(*pos, MapTile::Visited);
+    }
+
+    if let Some(guard) = patrol.guard {
+        grid.set(guard, MapTile::Guard(patrol.direction));
+    }
+
+    format!("{grid}\n{}", patrol.status())
+}
+
+/// Moves the guard forward the given number of times, turning whenever it's blocked.
+fn repl_step(patrol: &mut Patrol, arguments: &str) -> Result<String> {
+    let count: usize = match arguments.trim() {
+        "" => 1,
+        count => count.parse().context("expected a number of steps")?,
+    };
+
+    for _ in 0 .. count {
+        let Some(pos) = patrol.guard else { break };
+        let mut turns = 0;
+
+        // Keep turning until there's nothing in the way, which could take a while if the guard is boxed in.
+        let next_pos = loop {
+            let next_pos = pos.step_within(patrol.direction, patrol.grid.size());
+
+            match next_pos {
+                Some(next_pos) if patrol.grid.get(next_pos).is_some_and(MapTile::is_blocking) => {
+                    patrol.direction = patrol.direction.turn_right();
+                    turns += 1;
+
+                    if turns == 4 {
+                        bail!("the guard is boxed in on every side");
+                    }
+                }
+                next_pos => break next_pos,
+            }
+        };
+
+        patrol.guard = next_pos;
+        patrol.steps += 1;
+
+        if let Some(next_pos) = next_pos {
+            patrol.visited.insert(next_pos);
+        }
+    }
+
+    Ok(patrol.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repl_walks_the_guard() {
+        let mut session = REPL.unwrap()("#...\n...#\n^...\n").unwrap();
+        let mut run = |command: &str, arguments: &str| session.run(command, arguments).unwrap().unwrap();
+
+        assert_eq!(run("step", "2"), "after 2 step(s), the guard is at (1, 1) facing >, having visited 3 position(s)");
+        assert_eq!(
+            run("show-grid", ""),
+            "#...\nX>.#\nX...\nafter 2 step(s), the guard is at (1, 1) facing >, having visited 3 position(s)"
+        );
+        assert_eq!(run("reset", ""), "after 0 step(s), the guard is at (0, 2) facing ^, having visited 1 position(s)");
+        assert!(session.run("step", "x").unwrap().is_err());
+        assert!(session.run("jump", "").is_none());
+    }
+}
@@ -1,25 +1,35 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::fmt::Write;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 
-use crate::SolutionResult;
+use crate::error::Error;
+use crate::identify::{self, line_ratio};
+use crate::repl::{Open, Session};
+use crate::trace::trace;
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 type Input = Box<[(u64, Box<[u64]>)]>;
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    let iterator = BufReader::new(File::open(path)?).lines();
+/// The most operator combinations that the reference solutions will try for a single equation before giving up, since
+/// they'd otherwise take practically forever (or overflow) on long ones.
+const MAX_REFERENCE_COMBINATIONS: u64 = 1 << 24;
+
+fn input(string: &str) -> Result<Input> {
+    let iterator = string.lines();
     let capacity = iterator.size_hint().1.unwrap_or_else(|| iterator.size_hint().0);
     let mut list = Vec::with_capacity(capacity);
 
-    for result in iterator {
-        let line = result?;
-        let (target, values) = line.split_once(": ").unwrap();
+    for (index, line) in iterator.enumerate() {
+        let Some((target, values)) = line.split_once(": ") else {
+            return Err(Error::parse_at(index + 1, "expected a target and its values separated by ': '").into());
+        };
+        let parse = |number: &str| number.parse().map_err(|v| Error::parse_within(index + 1, line, number, v));
 
-        let target = target.parse()?;
+        let target = parse(target)?;
         // I love that you can collect into a result of a collection.
-        let values = values.split(" ").map(|v| v.parse()).collect::<Result<_, _>>()?;
+        // Splitting always yields at least one piece, so every equation is guaranteed to have a first value.
+        let values = values.split(" ").map(parse).collect::<Result<_, _>>()?;
 
         list.push((target, values));
     }
@@ -27,8 +37,59 @@ fn input(path: impl AsRef<Path>) -> Result<Input> {
     Ok(list.into_boxed_slice())
 }
 
+/// Generates the given number of random calibration equations.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    // Keeps targets small enough that concatenating another operand can never overflow.
+    const LIMIT: u64 = 1 << 48;
+
+    let mut output = String::with_capacity(size * 32);
+
+    for _ in 0 .. size {
+        let values: Vec<u64> = (0 .. random.between(2, 8)).map(|_| random.between(1, 999)).collect();
+        let mut target = values[0];
+
+        for value in &values[1 ..] {
+            // We fall back to addition whenever another operator would grow the target too large.
+            target = match random.below(3) {
+                0 => target.checked_mul(*value),
+                1 => format!("{target}{value}").parse().ok(),
+                _ => None,
+            }
+            .filter(|v| *v < LIMIT)
+            .unwrap_or(target + value);
+        }
+
+        // Nudge some of the targets so that they're (probably) impossible to hit.
+        if random.chance(1, 3) {
+            target += 1;
+        }
+
+        _ = writeln!(output, "{target}: {}", values.iter().map(u64::to_string).collect::<Vec<_>>().join(" "));
+    }
+
+    output
+}
+
+/// Returns how likely it is that the input is a list of calibration equations.
+pub fn identify(string: &str) -> f64 {
+    line_ratio(string, |line| {
+        line.split_once(": ").is_some_and(|(target, values)| {
+            identify::is_integer_list(target, " ") && identify::is_integer_list(values, " ")
+        })
+    })
+}
+
+/// An operator that can be placed between two values, which returns `None` if the result would overflow.
+type Operator = Box<dyn Fn(u64, u64) -> Option<u64>>;
+
+/// Adds a reachable target onto the running total, which huge enough targets can push past what a `u64` can hold.
+fn add_target(sum: u64, target: u64) -> Result<u64> {
+    sum.checked_add(target)
+        .ok_or_else(|| Error::Solver("the total calibration result doesn't fit in a u64".into()).into())
+}
+
 // Awful, awful recursive function. But it works!
-fn find(target: u64, current: u64, list: &[u64], index: usize, ops: &[Box<dyn Fn(u64, u64) -> u64>]) -> Option<u64> {
+fn find(target: u64, current: u64, list: &[u64], index: usize, ops: &[Operator]) -> Option<u64> {
     if target == current {
         return Some(target);
     } else if index >= list.len() || target < current {
@@ -38,32 +99,259 @@ fn find(target: u64, current: u64, list: &[u64], index: usize, ops: &[Box<dyn Fn
 
     // We basically call every given operator recursively until we find the target value.
     // This *does* break out early if the value is found, so it could be worse!
-    ops.iter().find_map(|f| self::find(target, f(current, list[index]), list, index + 1, ops))
+    // Anything that overflows is certainly larger than the target, so it's treated as a miss.
+    ops.iter().find_map(|f| self::find(target, f(current, list[index])?, list, index + 1, ops))
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
+pub fn solution_1(string: &str) -> SolutionResult {
     let mut sum = 0;
 
-    for (target, values) in self::input(path)? {
-        self::find(target, values[0], &values, 1, &[Box::from(|a, b| a + b), Box::from(|a, b| a * b)])
-            .inspect(|v| sum += v);
+    for (index, (target, values)) in self::input(string)?.into_iter().enumerate() {
+        match self::find(target, values[0], &values, 1, &[
+            Box::from(|a: u64, b| a.checked_add(b)),
+            Box::from(|a: u64, b| a.checked_mul(b)),
+        ]) {
+            Some(value) => sum = self::add_target(sum, value)?,
+            None => trace!("unsolvable", "equation {index} cannot reach {target}"),
+        }
     }
 
     Ok(sum as _)
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
+pub fn solution_2(string: &str) -> SolutionResult {
     let mut sum = 0;
 
-    for (target, values) in self::input(path)? {
-        self::find(target, values[0], &values, 1, &[
-            Box::from(|a, b| a + b),
-            Box::from(|a, b| a * b),
+    for (index, (target, values)) in self::input(string)?.into_iter().enumerate() {
+        match self::find(target, values[0], &values, 1, &[
+            Box::from(|a: u64, b| a.checked_add(b)),
+            Box::from(|a: u64, b| a.checked_mul(b)),
             // This works. It sucks, but it works.
-            Box::from(|a, b| format!("{a}{b}").parse().unwrap()),
-        ])
-        .inspect(|v| sum += v);
+            Box::from(|a, b| format!("{a}{b}").parse().ok()),
+        ]) {
+            Some(value) => sum = self::add_target(sum, value)?,
+            None => trace!("unsolvable", "equation {index} cannot reach {target}"),
+        }
     }
 
     Ok(sum as _)
 }
+
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+/// Tries every combination of the first `operators` operators (addition, multiplication, and concatenation, in that
+/// order) for every equation, returning the sum of the targets that can be reached.
+fn reference_sum(string: &str, operators: u64) -> SolutionResult {
+    let mut sum = 0;
+
+    for (index, (target, values)) in self::input(string)?.into_iter().enumerate() {
+        let Some((first, rest)) = values.split_first() else { continue };
+        let Some(combinations) = u32::try_from(rest.len())
+            .ok()
+            .and_then(|v| operators.checked_pow(v))
+            .filter(|v| *v <= MAX_REFERENCE_COMBINATIONS)
+        else {
+            return Err(Error::Solver(format!("equation {index} has too many values to brute force")).into());
+        };
+
+        let is_solvable = (0 .. combinations).any(|mut combination| {
+            let result = rest.iter().try_fold(*first, |current, value| {
+                let operator = combination % operators;
+
+                combination /= operators;
+
+                match operator {
+                    0 => current.checked_add(*value),
+                    1 => current.checked_mul(*value),
+                    _ => current.checked_mul(10_u64.checked_pow(value.to_string().len() as u32)?)?.checked_add(*value),
+                }
+            });
+
+            result == Some(target)
+        });
+
+        if is_solvable {
+            sum = self::add_target(sum, target)?;
+        }
+    }
+
+    Ok(sum)
+}
+
+fn reference_1(string: &str) -> SolutionResult {
+    self::reference_sum(string, 2)
+}
+
+fn reference_2(string: &str) -> SolutionResult {
+    self::reference_sum(string, 3)
+}
+
+/// Opens the list of equations within the REPL.
+pub const REPL: Option<Open> = Some(|string| Ok(Box::new(Calibration(self::input(string)?))));
+
+/// Every equation from the input.
+struct Calibration(Input);
+
+impl Session for Calibration {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("equation <N>", "shows the Nth equation (counting from 0), and how it can be solved"),
+            ("solve <target>: <values> [<operators>]", "solves a custom equation using any of `+*|` (all by default)"),
+            ("unsolvable [<operators>]", "lists every equation that can't be solved using any of `+*|`"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, arguments: &str) -> Option<Result<String>> {
+        Some(match command {
+            "equation" => self::repl_equation(self, arguments),
+            "solve" => self::repl_solve(arguments),
+            "unsolvable" => self::repl_unsolvable(self, arguments),
+            _ => return None,
+        })
+    }
+}
+
+/// Parses a set of operators, using every operator if none are given.
+fn repl_operators(string: &str) -> Result<Vec<char>> {
+    match string.trim() {
+        "" => Ok(vec!['+', '*', '|']),
+        string => {
+            string.chars().map(|c| if "+*|".contains(c) { Ok(c) } else { bail!("unknown operator '{c}'") }).collect()
+        }
+    }
+}
+
+/// Returns the operators that make the values reach the target, if there are any.
+fn repl_find(target: u64, values: &[u64], operators: &[char]) -> Option<Vec<char>> {
+    fn search(target: u64, current: u64, values: &[u64], operators: &[char], chosen: &mut Vec<char>) -> bool {
+        let Some((value, values)) = values.split_first() else { return current == target };
+
+        operators.iter().any(|operator| {
+            let next = match operator {
+                '+' => current.checked_add(*value),
+                '*' => current.checked_mul(*value),
+                _ => format!("{current}{value}").parse().ok(),
+            };
+
+            chosen.push(*operator);
+
+            if next.is_some_and(|next| search(target, next, values, operators, chosen)) {
+                return true;
+            }
+
+            chosen.pop();
+
+            false
+        })
+    }
+
+    let (first, values) = values.split_first()?;
+    let mut chosen = Vec::with_capacity(values.len());
+
+    search(target, *first, values, operators, &mut chosen).then_some(chosen)
+}
+
+/// Writes out an equation, including its operators if it can be solved.
+fn repl_describe(target: u64, values: &[u64], operators: &[char]) -> String {
+    let Some(chosen) = self::repl_find(target, values, operators) else {
+        let values = values.iter().map(u64::to_string).collect::<Vec<_>>().join(" ");
+
+        return format!("{target}: {values} cannot be solved");
+    };
+
+    let mut output = format!("{target} = {}", values[0]);
+
+    for (operator, value) in chosen.iter().zip(&values[1 ..]) {
+        match operator {
+            '|' => _ = write!(output, " || {value}"),
+            operator => _ = write!(output, " {operator} {value}"),
+        }
+    }
+
+    output
+}
+
+/// Shows an equation from the input, and how it can be solved.
+fn repl_equation(calibration: &Calibration, arguments: &str) -> Result<String> {
+    let index: usize = arguments.trim().parse().context("expected an equation number")?;
+    let Some((target, values)) = calibration.0.get(index) else {
+        bail!("there are only {} equations", calibration.0.len());
+    };
+
+    Ok(self::repl_describe(*target, values, &['+', '*', '|']))
+}
+
+/// Solves a custom equation.
+fn repl_solve(arguments: &str) -> Result<String> {
+    let Some((target, rest)) = arguments.split_once(':') else { bail!("expected an equation like `190: 10 19`") };
+    let target: u64 = target.trim().parse().context("invalid target")?;
+    let mut values = Vec::new();
+    let mut operators = "";
+
+    for value in rest.split_whitespace() {
+        match value.parse() {
+            Ok(value) => values.push(value),
+            Err(_) => operators = value,
+        }
+    }
+
+    if values.is_empty() {
+        bail!("expected at least one value");
+    }
+
+    Ok(self::repl_describe(target, &values, &self::repl_operators(operators)?))
+}
+
+/// Lists every equation that can't be solved.
+fn repl_unsolvable(calibration: &Calibration, arguments: &str) -> Result<String> {
+    let operators = self::repl_operators(arguments)?;
+    let unsolvable: Vec<String> = (calibration.0.iter().enumerate())
+        .filter(|(_, (target, values))| self::repl_find(*target, values, &operators).is_none())
+        .map(|(index, _)| index.to_string())
+        .collect();
+
+    Ok(format!("{} of {} equations cannot be solved: {}", unsolvable.len(), calibration.0.len(), unsolvable.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_give_up_on_long_equations() {
+        // Three operators across 44 gaps is more combinations than a u64 can even count.
+        let input = format!("45: {}\n", ["1"; 45].join(" "));
+        let error = self::reference_2(&input).unwrap_err();
+
+        assert!(matches!(Error::find(&error), Some(Error::Solver(_))), "{error}");
+        assert_eq!(self::solution_2(&input).unwrap(), 45);
+    }
+
+    /// Runs a REPL command against a session opened with the given input.
+    fn repl(input: &str, command: &str, arguments: &str) -> Result<String> {
+        let mut session = REPL.unwrap()(input)?;
+
+        session.run(command, arguments).expect("the command should exist")
+    }
+
+    #[test]
+    fn repl_explains_equations() {
+        let input = "190: 10 19\n7290: 6 8 6 15\n83: 17 5\n";
+
+        assert_eq!(self::repl(input, "equation", "0").unwrap(), "190 = 10 * 19");
+        assert_eq!(self::repl(input, "equation", "1").unwrap(), "7290 = 6 * 8 || 6 * 15");
+        assert_eq!(self::repl(input, "equation", "3").unwrap_err().to_string(), "there are only 3 equations");
+        assert_eq!(self::repl(input, "solve", "156: 15 6").unwrap(), "156 = 15 || 6");
+        assert_eq!(self::repl(input, "solve", "156: 15 6 +*").unwrap(), "156: 15 6 cannot be solved");
+        assert!(self::repl(input, "solve", "156: 15 6 -").is_err());
+    }
+
+    #[test]
+    fn repl_lists_unsolvable_equations() {
+        let input = "190: 10 19\n7290: 6 8 6 15\n83: 17 5\n";
+
+        assert_eq!(self::repl(input, "unsolvable", "").unwrap(), "1 of 3 equations cannot be solved: 2");
+        assert_eq!(self::repl(input, "unsolvable", "+*").unwrap(), "2 of 3 equations cannot be solved: 1, 2");
+    }
+}
@@ -1,51 +1,77 @@
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::num::NonZeroUsize;
 
 use anyhow::Result;
 
-use crate::SolutionResult;
-use crate::utility::grid::{Grid2D, Offset2D, Pos2D, read_to_char_grid};
+use crate::identify::grid_ratio;
+use crate::repl::Open;
+use crate::utility::grid::{Grid2D, Pos2D, Size2D, string_to_char_grid};
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 type Input = Grid2D<char>;
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    read_to_char_grid(path, |_, c| c.is_ascii_alphanumeric().then_some(c))
+fn input(string: &str) -> Result<Input> {
+    string_to_char_grid(string, |_, c| c.is_ascii_alphanumeric().then_some(c))
+}
+
+/// Generates a random square map of antennas with the given width.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    const FREQUENCIES: &[char] =
+        &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'a', 'b', 'c', 'x', 'y', 'z'];
+
+    let size = size.max(1);
+    let frequencies = &FREQUENCIES[.. (size / 8).clamp(1, FREQUENCIES.len())];
+    let mut output = String::with_capacity(size * (size + 1));
+
+    for pos in Size2D::square(NonZeroUsize::new(size).unwrap()).positions() {
+        output.push(match random.chance(1, 30) {
+            true => random.choose(frequencies).copied().unwrap_or('.'),
+            false => '.',
+        });
+
+        if pos.x() == size - 1 {
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// Returns how likely it is that the input is a map of antennas.
+pub fn identify(string: &str) -> f64 {
+    let ratio = grid_ratio(string, |c| c == '.' || c.is_ascii_alphanumeric());
+    let antennas = string.chars().filter(char::is_ascii_alphanumeric).count();
+    let empty = string.chars().filter(|c| *c == '.').count();
+
+    // Antennas are few and far between, which is what sets this apart from a word search.
+    if antennas > 0 && empty > antennas { ratio } else { ratio * 0.3 }
 }
 
 /// Returns a list of valid anode positions on either side of a pair of signals.
+///
+/// Without harmonics, that's just the two positions one whole gap past either signal, so neither signal counts (though
+/// any other antenna there does). With them, it's every position on the line through both signals, which means stepping
+/// by the smallest offset along that line rather than by the whole gap, since that'd skip over some of them.
 fn anodes_iter(
     grid: &Grid2D<char>,
-    signal: Option<&char>,
     position_1: Pos2D,
     position_2: Pos2D,
-    max_steps: Option<usize>,
+    harmonics: bool,
 ) -> impl Iterator<Item = Pos2D> {
-    // Calculate the difference of the two positions to use as an offset for anode positions.
-    let delta_x = position_2.x() as isize - position_1.x() as isize;
-    let delta_y = position_2.y() as isize - position_1.y() as isize;
-
-    // This isn't exact, but it's good enough. Thank you Pythagoras.
-    let max_steps = max_steps.unwrap_or_else(|| {
-        let w = grid.size().w().get();
-        let h = grid.size().h().get();
-
-        // Always round up so that we don't truncate and possibly miss a step.
-        (((w * w) + (h * h)) as f64).sqrt().ceil() as usize
-    });
-
-    // Iterator of increasingly larger offsets that are used to map anodes.
-    let offsets = (0 ..= max_steps as isize).map(move |v| Offset2D::new(delta_x * v, delta_y * v));
-    let anodes_1 = offsets.clone().filter_map(move |v| position_1.offset(-v));
-    let anodes_2 = offsets.filter_map(move |v| position_2.offset(v));
-
-    anodes_1.chain(anodes_2).filter(move |v| {
-        // We chain and filter out any positions that do not fit within the grid, or that overlap a matching signal.
-        v.is_contained_within(grid.size()) && grid.get(*v).is_none_or(|v| signal.is_none_or(|u| v != u))
-    })
+    let delta = position_2 - position_1;
+    let (step, count) = if harmonics { (delta.normalized(), usize::MAX) } else { (delta, 1) };
+    // With harmonics, walking forwards from the first signal passes through the second one and everything between.
+    let (start, forward) = if harmonics { (Some(position_1), position_1) } else { (None, position_2) };
+
+    start
+        .into_iter()
+        .chain(position_1.ray(-step, grid.size()).take(count))
+        .chain(forward.ray(step, grid.size()).take(count))
 }
 
 /// Count all unique anodes present in the given grid of signals.
-fn count_anodes(grid: &Grid2D<char>, max_steps: Option<usize>, no_overlap: bool) -> usize {
+fn count_anodes(grid: &Grid2D<char>, harmonics: bool) -> usize {
     // Group all signal positions by their characters.
     let mut signals = HashMap::<char, HashSet<Pos2D>>::new();
 
@@ -56,20 +82,89 @@ fn count_anodes(grid: &Grid2D<char>, max_steps: Option<usize>, no_overlap: bool)
     // And then track all unique anode positions.
     let mut anodes = HashSet::<Pos2D>::new();
 
-    for (signal, position_1, position_2) in signals.iter().flat_map(|(signal, set)| {
+    for (position_1, position_2) in signals.values().flat_map(|set| {
         // Only allow positions that are non-equal to pass through.
-        set.iter().flat_map(move |a| set.iter().filter_map(move |b| (a != b).then_some((signal, *a, *b))))
+        set.iter().flat_map(move |a| set.iter().filter_map(move |b| (a != b).then_some((*a, *b))))
     }) {
-        anodes.extend(self::anodes_iter(grid, no_overlap.then_some(signal), position_1, position_2, max_steps));
+        anodes.extend(self::anodes_iter(grid, position_1, position_2, harmonics));
     }
 
     anodes.len()
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    self::input(path).map(|grid| self::count_anodes(&grid, Some(1), true) as _)
+pub fn solution_1(string: &str) -> SolutionResult {
+    self::input(string).map(|grid| self::count_anodes(&grid, false) as _)
+}
+
+pub fn solution_2(string: &str) -> SolutionResult {
+    self::input(string).map(|grid| self::count_anodes(&grid, true) as _)
+}
+
+/// Deliberately simple solutions, used to check the ones above.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), Some(self::reference_2)];
+
+/// Returns every ordered pair of distinct antennas that share a frequency.
+fn reference_pairs(grid: &Grid2D<char>) -> Vec<(Pos2D, Pos2D)> {
+    let antennas: Vec<(Pos2D, char)> = grid.iter().filter_map(|(p, c)| c.map(|c| (p, c))).collect();
+
+    antennas
+        .iter()
+        .flat_map(|a| antennas.iter().filter(move |b| a.0 != b.0 && a.1 == b.1).map(move |b| (a.0, b.0)))
+        .collect()
+}
+
+fn reference_1(string: &str) -> SolutionResult {
+    let grid = self::input(string)?;
+    let mut antinodes = HashSet::new();
+
+    for (a, b) in self::reference_pairs(&grid) {
+        // The antinode sits just as far past `b` as `b` is from `a`.
+        let antinode = b + (b - a);
+
+        antinodes.extend(antinode.filter(|v| v.is_contained_within(grid.size())));
+    }
+
+    Ok(antinodes.len() as _)
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
-    self::input(path).map(|grid| self::count_anodes(&grid, None, false) as _)
+fn reference_2(string: &str) -> SolutionResult {
+    let grid = self::input(string)?;
+    let pairs = self::reference_pairs(&grid);
+
+    // A position is an antinode if it's on the same line as any pair, which is when the cross product is zero.
+    Ok(grid
+        .size()
+        .positions()
+        .filter(|c| {
+            pairs.iter().any(|(a, b)| {
+                let (ab, ac) = (*b - *a, *c - *a);
+
+                ab.x() * ac.y() == ab.y() * ac.x()
+            })
+        })
+        .count() as _)
+}
+
+/// This day doesn't have any commands of its own within the REPL.
+pub const REPL: Option<Open> = None;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anodes_can_land_on_other_antennas() {
+        // Each pair's anode sits on the third antenna, which still counts.
+        assert_eq!(self::solution_1("0.0.0\n").unwrap(), 2);
+        assert_eq!(self::reference_1("0.0.0\n").unwrap(), 2);
+    }
+
+    #[test]
+    fn harmonics_fill_in_the_gap() {
+        // The antennas are two steps apart diagonally, so the middle of the grid is in line with both of them.
+        let input = "..0\n...\n0..\n";
+
+        assert_eq!(self::solution_2(input).unwrap(), 3);
+        assert_eq!(self::reference_2(input).unwrap(), 3);
+    }
 }
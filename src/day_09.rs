@@ -1,23 +1,23 @@
 use std::cell::RefCell;
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
 
-use anyhow::{Result, bail};
+use anyhow::Result;
 
-use crate::SolutionResult;
+use crate::error::Error;
+use crate::repl::Open;
+use crate::utility::random::Random;
+use crate::{Solution, SolutionResult};
 
 type Input = Box<[Block]>;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Block {
-    Named(u16, u8),
+    Named(u32, u8),
     Empty(u8),
 }
 
 impl Block {
     /// Returns the id that should be used to represent this block.
-    pub const fn id(&self) -> Option<u16> {
+    pub const fn id(&self) -> Option<u32> {
         match self {
             Block::Named(digit, _) => Some(*digit),
             Block::Empty(_) => None,
@@ -32,35 +32,65 @@ impl Block {
     }
 }
 
-fn input(path: impl AsRef<Path>) -> Result<Input> {
-    let mut iterator = File::open(path)?.bytes();
-    let (min_capacity, max_capacity) = iterator.size_hint();
-    let capacity = max_capacity.unwrap_or(min_capacity);
-
-    let mut list = Vec::with_capacity(capacity);
+fn input(string: &str) -> Result<Input> {
+    let mut list = Vec::with_capacity(string.len());
     let mut next_id = 0;
     let mut use_empty = false;
 
-    while let Some(byte) = iterator.next().transpose()? {
-        let character = char::from(byte);
+    for (y, line) in string.lines().enumerate() {
+        for (x, character) in line.chars().enumerate() {
+            let Some(digit) = character.to_digit(10).map(|v| v as u8) else {
+                return Err(Error::parse_at_column(y + 1, x + 1, format!("invalid digit {character:?}")).into());
+            };
 
-        if let Some(digit) = character.to_digit(10).map(|v| v as u8) {
             list.push(if use_empty { Block::Empty(digit) } else { Block::Named(next_id, digit) });
 
             use_empty = !use_empty;
-            next_id += use_empty as u16;
-        } else if character != '\n' {
-            bail!("invalid digit: {character:?}");
+
+            // Real inputs have around ten thousand files, but there's no reason to trust that every input does.
+            if use_empty {
+                let Some(id) = next_id.checked_add(1) else {
+                    return Err(Error::parse_at_column(y + 1, x + 1, "too many files").into());
+                };
+
+                next_id = id;
+            }
         }
     }
 
     Ok(list.into_boxed_slice())
 }
 
-pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
-    let blocks = self::input(path)?;
+/// Generates a random disk map with the given number of digits.
+pub fn generate(random: &mut Random, size: usize) -> String {
+    let mut output = String::with_capacity(size + 1);
+
+    for index in 0 .. size {
+        // Files always take up at least one block, whereas free space can be empty.
+        let digit = if index % 2 == 0 { random.between(1, 9) } else { random.between(0, 9) };
+
+        output.extend(char::from_digit(digit as u32, 10));
+    }
+
+    output.push('\n');
+    output
+}
+
+/// Returns how likely it is that the input is a disk map.
+pub fn identify(string: &str) -> f64 {
+    let lines: Vec<&str> = string.lines().filter(|v| !v.is_empty()).collect();
+
+    match lines.as_slice() {
+        [line] if line.bytes().all(|b| b.is_ascii_digit()) => 1.0,
+        [line] => line.bytes().filter(u8::is_ascii_digit).count() as f64 / line.len() as f64 * 0.5,
+        _ => 0.0,
+    }
+}
+
+pub fn solution_1(string: &str) -> SolutionResult {
+    let blocks = self::input(string)?;
 
-    let disk: Box<[_]> = blocks.iter().flat_map(|v| std::iter::repeat(v.id()).take(v.len())).collect();
+    let disk: Box<[_]> = blocks.iter().flat_map(|v| std::iter::repeat_n(v.id(), v.len())).collect();
     let disk = RefCell::new(disk);
 
     let next_none_index = std::iter::from_fn(|| disk.borrow().iter().enumerate().find(|v| v.1.is_none()).map(|v| v.0));
@@ -74,6 +104,34 @@ pub fn solution_1(path: impl AsRef<Path>) -> SolutionResult {
     Ok(disk.borrow().iter().enumerate().map(|(i, v)| v.map_or(0, |v| v as u64) * i as u64).sum())
 }
 
-pub fn solution_2(path: impl AsRef<Path>) -> SolutionResult {
-    todo!("solution not yet implemented for {}", path.as_ref().to_string_lossy())
+pub fn solution_2(string: &str) -> SolutionResult {
+    todo!("solution not yet implemented for an input of {} bytes", string.len())
+}
+
+/// Deliberately simple solutions, used to check the ones above.
+///
+/// There's no reference for the second solution yet, since there's no second solution yet.
+pub const REFERENCES: [Option<Solution>; 2] = [Some(self::reference_1), None];
+
+fn reference_1(string: &str) -> SolutionResult {
+    let blocks = self::input(string)?;
+    let mut disk: Vec<Option<u32>> = blocks.iter().flat_map(|v| std::iter::repeat_n(v.id(), v.len())).collect();
+
+    // Move the last file block into the first free block, one at a time, until there are no gaps left.
+    while let Some(free_index) = disk.iter().position(Option::is_none) {
+        while disk.last().is_some_and(Option::is_none) {
+            disk.pop();
+        }
+
+        if free_index >= disk.len() {
+            break;
+        }
+
+        disk[free_index] = disk.pop().flatten();
+    }
+
+    Ok(disk.iter().enumerate().map(|(i, v)| i as u64 * v.map_or(0, |v| v as u64)).sum())
 }
+
+/// This day doesn't have any commands of its own within the REPL.
+pub const REPL: Option<Open> = None;
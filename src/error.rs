@@ -0,0 +1,154 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crate::utility::json::Json;
+
+/// A failure that the runner knows how to tell apart from any other.
+///
+/// These are still passed around as [`anyhow::Error`]s, and are found again by downcasting. Anything that isn't one of
+/// these is treated as a generic failure.
+#[derive(Debug)]
+pub enum Error {
+    /// The given day has not been mapped to any solutions.
+    UnknownDay(u8),
+    /// The given day doesn't have the given part.
+    MissingPart(u8, u8),
+    /// The input file could not be read.
+    Input(PathBuf, std::io::Error),
+    /// The input could not be parsed.
+    Parse(Option<Location>, String),
+    /// The solution failed, or panicked, while solving a valid input.
+    Solver(String),
+}
+
+impl Error {
+    /// Creates a new parse error at the given line (counting from 1).
+    pub fn parse_at(line: usize, message: impl Display) -> Self {
+        Self::Parse(Some(Location { line, column: None }), message.to_string())
+    }
+
+    /// Creates a new parse error at the given line and column (both counting from 1).
+    pub fn parse_at_column(line: usize, column: usize, message: impl Display) -> Self {
+        Self::Parse(Some(Location { line, column: Some(column) }), message.to_string())
+    }
+
+    /// Creates a new parse error about a piece of the given line (counting from 1), which must be a slice of that line.
+    ///
+    /// The column is worked out from where the piece sits within the line, and the piece itself is quoted.
+    pub fn parse_within(line_number: usize, line: &str, piece: &str, message: impl Display) -> Self {
+        // This only makes sense for slices of the line, but anything else just ends up at the start of it.
+        let offset = (piece.as_ptr() as usize).checked_sub(line.as_ptr() as usize).filter(|v| *v <= line.len());
+        let column = line[.. offset.unwrap_or(0)].chars().count() + 1;
+
+        Self::parse_at_column(line_number, column, format!("{message} in '{piece}'"))
+    }
+
+    /// Creates a new parse error without a location, for problems with the input as a whole.
+    pub fn parse(message: impl Display) -> Self {
+        Self::Parse(None, message.to_string())
+    }
+
+    /// Finds the first [`Error`] within the given error's chain of causes, if any.
+    pub fn find(error: &anyhow::Error) -> Option<&Self> {
+        error.chain().find_map(|v| v.downcast_ref())
+    }
+
+    /// Turns an error returned by a solution into an [`Error`], treating anything unrecognized as a solver error.
+    pub fn from_solution(error: anyhow::Error) -> Self {
+        error.downcast().unwrap_or_else(|error| Self::Solver(format!("{error:#}")))
+    }
+
+    /// Returns a short, machine-readable name for this kind of error.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::UnknownDay(..) => "unknown_day",
+            Self::MissingPart(..) => "missing_part",
+            Self::Input(..) => "input",
+            Self::Parse(..) => "parse",
+            Self::Solver(..) => "solver",
+        }
+    }
+
+    /// Returns the process exit code that should be used if this error ends the program.
+    ///
+    /// `1` is left for any error that isn't one of these.
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::UnknownDay(..) => 2,
+            Self::MissingPart(..) => 3,
+            Self::Input(..) => 4,
+            Self::Parse(..) => 5,
+            Self::Solver(..) => 6,
+        }
+    }
+
+    /// Returns the exit code that should be used if the given error ends the program.
+    pub fn exit_code_of(error: &anyhow::Error) -> ExitCode {
+        ExitCode::from(Self::find(error).map_or(1, Self::exit_code))
+    }
+
+    /// Returns the entries that describe this error within a JSON object.
+    ///
+    /// These always include the error's message as `error` and its kind as `kind`, followed by any details.
+    pub fn json_entries(&self) -> Vec<(&'static str, Json)> {
+        let mut entries = vec![("error", Json::from(self.to_string())), ("kind", Json::from(self.kind()))];
+
+        match self {
+            Self::UnknownDay(day) => entries.push(("day", Json::from(*day))),
+            Self::MissingPart(day, part) => entries.extend([("day", Json::from(*day)), ("part", Json::from(*part))]),
+            Self::Input(path, _) => entries.push(("path", Json::from(path.display().to_string()))),
+            Self::Parse(Some(location), _) => {
+                entries.push(("line", Json::from(location.line)));
+                entries.extend(location.column.map(|v| ("column", Json::from(v))));
+            }
+            Self::Parse(None, _) | Self::Solver(_) => {}
+        }
+
+        entries
+    }
+
+    /// Returns the entries that describe the given error within a JSON object, just like [`Error::json_entries`].
+    ///
+    /// Errors that aren't [`Error`]s have a kind of `other`.
+    pub fn json_entries_of(error: &anyhow::Error) -> Vec<(&'static str, Json)> {
+        match Self::find(error) {
+            Some(error) => error.json_entries(),
+            None => vec![("error", Json::from(format!("{error:#}"))), ("kind", Json::from("other"))],
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownDay(day) => write!(f, "day {day} has not been mapped to any solutions"),
+            Self::MissingPart(day, part) => write!(f, "day {day} has no part {part}, it must be either 1 or 2"),
+            Self::Input(path, error) => write!(f, "failed to read '{}': {error}", path.display()),
+            Self::Parse(Some(location), message) => write!(f, "invalid input at {location}: {message}"),
+            Self::Parse(None, message) => write!(f, "invalid input: {message}"),
+            Self::Solver(message) => f.write_str(message),
+        }
+    }
+}
+
+// The I/O error is already part of the message, so there's no point in also returning it as a source.
+impl std::error::Error for Error {}
+
+/// Where a parse error happened within the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// The line, counting from 1.
+    pub line: usize,
+    /// The column, counting from 1, if it's known.
+    pub column: Option<usize>,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {column}", self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{Result, bail};
+
+use crate::{DAYS, Day};
+
+/// The lowest confidence that [`guess`] will accept.
+pub const MINIMUM_CONFIDENCE: f64 = 0.5;
+
+/// Returns every registered day alongside how confident it is that the input belongs to it, from most to least
+/// confident.
+///
+/// Confidence values range from `0.0` (definitely not) to `1.0` (definitely).
+pub fn rank(input: &str) -> Vec<(&'static Day, f64)> {
+    let mut ranking: Vec<_> = DAYS.iter().map(|day| (day, (day.identify)(input).clamp(0.0, 1.0))).collect();
+
+    // Ties are broken by day number, just to keep things predictable.
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.number.cmp(&b.0.number)));
+    ranking
+}
+
+/// Returns the day that the input most likely belongs to, failing if no day is confident enough.
+pub fn guess(input: &str) -> Result<&'static Day> {
+    let ranking = self::rank(input);
+
+    match ranking.first() {
+        Some(&(day, confidence)) if confidence >= MINIMUM_CONFIDENCE => Ok(day),
+        Some(&(day, confidence)) => bail!(
+            "the input could not be identified (the best guess was day {} at {:.1}%)",
+            day.number,
+            confidence * 100.0
+        ),
+        None => bail!("the input could not be identified, since no days have been registered"),
+    }
+}
+
+/// Returns the fraction of the input's non-empty lines that satisfy the given predicate.
+pub fn line_ratio(input: &str, predicate: impl FnMut(&str) -> bool) -> f64 {
+    let lines: Vec<&str> = input.lines().filter(|v| !v.trim().is_empty()).collect();
+
+    if lines.is_empty() {
+        return 0.0;
+    }
+
+    lines.iter().copied().map(predicate).filter(|v| *v).count() as f64 / lines.len() as f64
+}
+
+/// Returns the fraction of the input's characters that satisfy the given predicate, assuming that it's a grid.
+///
+/// Inputs that aren't rectangular, or that are only a single line, aren't considered grids at all.
+pub fn grid_ratio(input: &str, predicate: impl FnMut(char) -> bool) -> f64 {
+    let lines: Vec<&str> = input.lines().filter(|v| !v.is_empty()).collect();
+    let Some(width) = lines.first().map(|v| v.chars().count()) else { return 0.0 };
+
+    if lines.len() < 2 || lines.iter().any(|v| v.chars().count() != width) {
+        return 0.0;
+    }
+
+    lines.iter().flat_map(|v| v.chars()).map(predicate).filter(|v| *v).count() as f64 / (width * lines.len()) as f64
+}
+
+/// Returns whether the given string is made up entirely of integers separated by the given separator.
+pub fn is_integer_list(string: &str, separator: &str) -> bool {
+    !string.is_empty() && string.split(separator).all(|v| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit()))
+}
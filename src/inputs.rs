@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use crate::error::Error;
+use crate::utility::cipher::{self, KEY_LENGTH, Key, NONCE_LENGTH, Nonce};
+
+/// The environment variable containing the key used to seal and unseal inputs, written as hexadecimal.
+pub const KEY_VARIABLE: &str = "AOC_INPUT_KEY";
+/// The bytes at the start of every sealed file, which also double as a format version.
+const MAGIC: &[u8] = b"aoc-sealed-v1\n";
+/// The extension appended to the name of every sealed file.
+const EXTENSION: &str = "sealed";
+
+/// Reads the input file at the given path.
+///
+/// If the file doesn't exist but a sealed copy of it does, the sealed copy is decrypted in memory instead, so long as
+/// a key is present within the environment.
+pub fn read(path: &Path) -> Result<String> {
+    let sealed_path = self::sealed_path(path);
+
+    if path.exists() || !sealed_path.exists() {
+        return std::fs::read_to_string(path).map_err(|v| Error::Input(path.to_path_buf(), v).into());
+    }
+
+    let Some(key) = self::key()? else {
+        bail!("'{}' is only available sealed, and {KEY_VARIABLE} has not been set", path.display());
+    };
+    let sealed = std::fs::read(&sealed_path).map_err(|v| Error::Input(sealed_path.clone(), v))?;
+
+    self::unseal(&key, path, &sealed).with_context(|| format!("failed to unseal '{}'", sealed_path.display()))
+}
+
+/// Returns the key contained within the environment, if one has been set.
+pub fn key() -> Result<Option<Key>> {
+    let Ok(value) = std::env::var(KEY_VARIABLE) else { return Ok(None) };
+    let value = value.trim();
+
+    if value.len() != KEY_LENGTH * 2 || !value.is_ascii() {
+        bail!("{KEY_VARIABLE} must contain exactly {} hexadecimal digits", KEY_LENGTH * 2);
+    }
+
+    let mut key = [0; KEY_LENGTH];
+
+    for (byte, digits) in key.iter_mut().zip(value.as_bytes().chunks_exact(2)) {
+        // The value is known to be ASCII, so every pair of bytes is also a valid string.
+        let digits = std::str::from_utf8(digits).unwrap_or_default();
+
+        *byte = u8::from_str_radix(digits, 16)
+            .with_context(|| format!("{KEY_VARIABLE} contains an invalid hexadecimal digit within '{digits}'"))?;
+    }
+
+    Ok(Some(key))
+}
+
+/// Returns a new random key.
+pub fn generate_key() -> Result<Key> {
+    self::random_bytes()
+}
+
+/// Returns the path of the sealed copy of the input file at the given path.
+pub fn sealed_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+
+    path.push(".");
+    path.push(EXTENSION);
+
+    PathBuf::from(path)
+}
+
+/// Returns the path of the input file that the sealed file at the given path is a copy of, if it's a sealed file.
+pub fn unsealed_path(path: &Path) -> Option<PathBuf> {
+    (path.extension()? == EXTENSION).then(|| path.with_extension(""))
+}
+
+/// Encrypts the contents of the input file at the given path.
+pub fn seal(key: &Key, path: &Path, contents: &str) -> Result<Vec<u8>> {
+    let nonce: Nonce = self::random_bytes()?;
+    let mut sealed = MAGIC.to_vec();
+
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&cipher::seal(key, &nonce, self::associated_data(path), contents.as_bytes()));
+
+    Ok(sealed)
+}
+
+/// Decrypts the sealed contents of the input file at the given path.
+pub fn unseal(key: &Key, path: &Path, sealed: &[u8]) -> Result<String> {
+    let Some(sealed) = sealed.strip_prefix(MAGIC) else { bail!("the file is not a sealed input") };
+    let Some((nonce, sealed)) = sealed.split_first_chunk::<NONCE_LENGTH>() else { bail!("the file is truncated") };
+
+    let contents = cipher::open(key, nonce, self::associated_data(path), sealed)?;
+
+    String::from_utf8(contents).context("the unsealed input is not valid UTF-8")
+}
+
+/// Returns the data that's authenticated alongside an input file's contents.
+///
+/// This is just the file's name, which stops anybody from renaming `day_1.txt.sealed` to `day_2.txt.sealed` and having
+/// it quietly decrypt into the wrong day's input.
+fn associated_data(path: &Path) -> &[u8] {
+    path.file_name().map_or(&[], |v| v.as_encoded_bytes())
+}
+
+/// Returns a bunch of random bytes from the operating system.
+///
+/// Our own [`Random`](crate::utility::random::Random) would make for terrible keys and nonces, so it's not used here.
+#[cfg(unix)]
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    use std::io::Read;
+
+    let mut bytes = [0; N];
+
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut v| v.read_exact(&mut bytes))
+        .context("failed to read random bytes from '/dev/urandom'")?;
+
+    Ok(bytes)
+}
+
+/// Fails, since the only source of random bytes we know of is `/dev/urandom`.
+///
+/// This only affects creating keys and sealing inputs, since unsealing doesn't need anything random.
+#[cfg(not(unix))]
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    bail!("creating keys and sealing inputs needs '/dev/urandom', which only Unix-like systems have")
+}
@@ -1,9 +1,17 @@
 #![feature(hash_set_entry)]
 
-use std::io::{Write, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 
+use self::arguments::Arguments;
+use self::error::Error;
+use self::utility::random::Random;
+
+mod arguments;
+mod cache;
+mod crash;
 mod day_01;
 mod day_02;
 mod day_03;
@@ -13,65 +21,158 @@ mod day_06;
 mod day_07;
 mod day_08;
 mod day_09;
+mod error;
+mod identify;
+mod inputs;
+mod outcome;
+mod repl;
+mod trace;
+
+mod command {
+    pub mod batch;
+    pub mod dashboard;
+    pub mod differential;
+    pub mod fuzz;
+    pub mod generate;
+    pub mod identify;
+    pub mod inputs;
+    pub mod leaderboard;
+    pub mod repl;
+    pub mod replay;
+    pub mod run;
+    pub mod serve;
+}
 
 #[allow(unused)]
 mod utility {
+    pub mod cipher;
     pub mod grid;
+    pub mod hash;
+    pub mod json;
+    pub mod random;
 }
 
 // I use a type alias here in case I ever need to change the integer size.
 pub type SolutionResult = Result<u64>;
 
-macro_rules! match_arguments {
-    (($argument_day:expr, $argument_solution:expr, $argument_example:expr) {$(
-        $specified_day:literal => $specified_module:ident
-    ),+ $(,)? }) => {{
-        // Informs the function what file path should be used to access its data.
-        #[inline]
-        fn __solution_path(number: u8, example: bool) -> impl ::std::convert::AsRef<::std::path::Path> {
-            ::std::format!("./data/day_{number}{}.txt", if example { "_example" } else { "" })
+/// A function that solves one part of a day's puzzle using the contents of an input file.
+pub type Solution = fn(&str) -> SolutionResult;
+
+/// A day that has been mapped to its solutions and tooling.
+#[derive(Clone, Copy, Debug)]
+pub struct Day {
+    /// The day's number.
+    pub number: u8,
+    /// The day's solutions, in order.
+    pub solutions: [Solution; 2],
+    /// The day's deliberately simple reference solutions, used to check the real ones.
+    pub references: [Option<Solution>; 2],
+    /// Generates a random input of roughly the given size.
+    pub generate: fn(&mut Random, usize) -> String,
+    /// Returns how confident the day is that the given input belongs to it, from `0.0` to `1.0`.
+    pub identify: fn(&str) -> f64,
+    /// Parses an input into a session for the REPL, if the day has any commands of its own.
+    pub repl: Option<repl::Open>,
+}
+
+impl Day {
+    /// Returns the day mapped to the given number.
+    pub fn find(number: u8) -> Result<&'static Self> {
+        match DAYS.iter().find(|v| v.number == number) {
+            Some(day) => Ok(day),
+            None => Err(Error::UnknownDay(number).into()),
         }
+    }
 
-        match ($argument_day, $argument_solution) {
-            // We auto-fill both solution matches for each given day, under the assumption that I actually finished both
-            // solutions for every given day. Let's hope I have both the motivation and skill, shall we?
-            $(
-                ($specified_day, 1) => $crate::$specified_module::solution_1(__solution_path($specified_day, $argument_example)),
-                ($specified_day, 2) => $crate::$specified_module::solution_2(__solution_path($specified_day, $argument_example)),
-            )+
+    /// Returns the solution for the given part.
+    pub fn solution(&self, part: u8) -> Result<Solution> {
+        match part {
+            1 | 2 => Ok(self.solutions[part as usize - 1]),
             // I was today years old when I figured out that you can use this syntax (referring to the `0 | 3..`).
             // This is probably less efficient than just checking if it's 1 or 2 directly but *oh well*.
-            (_, 0 | 3 ..) => ::anyhow::bail!("the solution must be either 1 or 2"),
-            _ => ::anyhow::bail!("the given day number has not been mapped to any solutions"),
+            0 | 3 .. => Err(Error::MissingPart(self.number, part).into()),
         }
-    }};
+    }
+
+    /// Returns the reference solution for the given part, if one has been registered.
+    pub fn reference(&self, part: u8) -> Result<Option<Solution>> {
+        self.solution(part).map(|_| self.references[part as usize - 1])
+    }
+
+    /// Returns the file path that should be used to access this day's data.
+    pub fn input_path(&self, example: bool) -> PathBuf {
+        PathBuf::from(format!("./data/day_{}{}.txt", self.number, if example { "_example" } else { "" }))
+    }
+
+    /// Reads this day's data, transparently unsealing it if only a sealed copy is available.
+    pub fn read_input(&self, example: bool) -> Result<String> {
+        inputs::read(&self.input_path(example))
+    }
 }
 
-fn main() -> Result<()> {
+macro_rules! register_days {
+    ($($specified_day:literal => $specified_module:ident),+ $(,)?) => {
+        /// Every day that has been mapped to a set of solutions.
+        pub const DAYS: &[Day] = &[$(
+            Day {
+                number: $specified_day,
+                // We auto-fill both solutions for each given day, under the assumption that I actually finished both
+                // solutions for every given day. Let's hope I have both the motivation and skill, shall we?
+                solutions: [$crate::$specified_module::solution_1, $crate::$specified_module::solution_2],
+                references: $crate::$specified_module::REFERENCES,
+                generate: $crate::$specified_module::generate,
+                identify: $crate::$specified_module::identify,
+                repl: $crate::$specified_module::REPL,
+            }
+        ),+];
+    };
+}
+
+register_days! {
+    1 => day_01,
+    2 => day_02,
+    3 => day_03,
+    4 => day_04,
+    5 => day_05,
+    6 => day_06,
+    7 => day_07,
+    8 => day_08,
+    9 => day_09,
+}
+
+fn main() -> ExitCode {
     // This is for the sake of debugging errors or panics, without having to remember to type out `RUST_BACKTRACE=1`
     // before every command, while I'm trying to finish each day in a timely manner.
     //
     // Safety: Nothing else is currently accessing the environment, as this is the first line.
     unsafe { std::env::set_var("RUST_BACKTRACE", "1") };
 
-    let mut arguments = std::env::args().skip(1);
-
     // I'm gonna give `clap` a run for its money with this one /j
-    let expected_day: u8 = arguments.next().ok_or_else(|| anyhow!("missing day"))?.parse()?;
-    let expected_solution: u8 = arguments.next().ok_or_else(|| anyhow!("missing solution"))?.parse()?;
-    let use_examples = arguments.next().is_some_and(|v| v.parse::<u8>().is_ok_and(|v| v == 1));
-
-    let solution = match_arguments!((expected_day, expected_solution, use_examples) {
-        1 => day_01,
-        2 => day_02,
-        3 => day_03,
-        4 => day_04,
-        5 => day_05,
-        6 => day_06,
-        7 => day_07,
-        8 => day_08,
-        9 => day_09,
-    })?;
-
-    stdout().write_fmt(format_args!("{solution}\n")).map_err(Into::into)
+    let arguments = Arguments::from_env();
+
+    let result = match arguments.peek() {
+        Some("batch") => command::batch::run(arguments.skip()),
+        Some("dashboard") => command::dashboard::run(arguments.skip()),
+        Some("differential") => command::differential::run(arguments.skip()),
+        Some("fuzz") => command::fuzz::run(arguments.skip()),
+        Some("generate") => command::generate::run(arguments.skip()),
+        Some("identify") => command::identify::run(arguments.skip()),
+        Some("inputs") => command::inputs::run(arguments.skip()),
+        Some("leaderboard") => command::leaderboard::run(arguments.skip()),
+        Some("repl") => command::repl::run(arguments.skip()),
+        Some("replay") => command::replay::run(arguments.skip()),
+        Some("run") => command::run::run(arguments.skip()),
+        Some("serve") => command::serve::run(arguments.skip()),
+        _ => command::run::run(arguments),
+    };
+
+    // This is the same as what returning the error would print, except that we get to pick the exit code.
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+
+            Error::exit_code_of(&error)
+        }
+    }
 }
@@ -0,0 +1,53 @@
+use std::any::Any;
+use std::fmt::Display;
+use std::panic::catch_unwind;
+
+use crate::Solution;
+
+/// The result of running a solution against a single input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(u64),
+    Error(String),
+    Panic(String),
+}
+
+impl Outcome {
+    /// Runs the given solution, catching any panics.
+    pub fn of(solution: Solution, input: &str) -> Self {
+        match catch_unwind(|| solution(input)) {
+            Ok(Ok(answer)) => Self::Answer(answer),
+            Ok(Err(error)) => Self::Error(error.to_string()),
+            Err(payload) => Self::Panic(self::panic_message(payload.as_ref())),
+        }
+    }
+
+    /// Returns whether both outcomes should be treated as the same.
+    ///
+    /// Error and panic messages aren't compared, since there's no reason for a reference to fail in the same way.
+    pub fn agrees_with(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Answer(lhs), Self::Answer(rhs)) => lhs == rhs,
+            (Self::Error(_), Self::Error(_)) | (Self::Panic(_), Self::Panic(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Answer(answer) => write!(f, "answered {answer}"),
+            Self::Error(error) => write!(f, "failed with '{error}'"),
+            Self::Panic(message) => write!(f, "panicked with '{message}'"),
+        }
+    }
+}
+
+/// Returns the message contained within a panic's payload.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => (*message).to_string(),
+        None => payload.downcast_ref::<String>().cloned().unwrap_or_else(|| "unknown panic".to_string()),
+    }
+}
@@ -0,0 +1,16 @@
+use anyhow::Result;
+
+/// A parsed input that can be poked at interactively using the `repl` command.
+///
+/// Every day that wants its own commands implements this for whatever it parses its input into, then registers a
+/// function that opens it as its `REPL`.
+pub trait Session {
+    /// Returns every command that this session understands, as pairs of usages and descriptions.
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+
+    /// Runs the given command, returning its output, or `None` if it isn't one of this session's commands.
+    fn run(&mut self, command: &str, arguments: &str) -> Option<Result<String>>;
+}
+
+/// Parses an input into a new [`Session`].
+pub type Open = fn(&str) -> Result<Box<dyn Session>>;
@@ -0,0 +1,91 @@
+use std::fmt::Arguments;
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::utility::json::Json;
+
+/// Whether tracing has been enabled.
+///
+/// This is checked before any trace arguments are evaluated, so a disabled trace call costs a single relaxed load.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Where trace events are written to once enabled.
+static TRACER: Mutex<Option<Tracer>> = Mutex::new(None);
+
+/// Records an event that explains how a solution arrived at its answer.
+///
+/// The first argument is a short, machine-friendly event name, and the rest is a format string describing the event.
+/// None of the arguments are evaluated unless tracing has been enabled.
+///
+/// ```ignore
+/// trace!("unsafe_report", "report {index} unsafe at index {level}");
+/// ```
+macro_rules! trace {
+    ($event:literal, $($argument:tt)+) => {
+        if $crate::trace::is_enabled() {
+            $crate::trace::emit(::std::module_path!(), $event, ::std::format_args!($($argument)+));
+        }
+    };
+}
+
+pub(crate) use trace;
+
+/// The format that trace events are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+/// The destination of every trace event.
+struct Tracer {
+    /// The format that events are written in.
+    format: Format,
+    /// The writer that receives events.
+    output: Box<dyn Write + Send>,
+}
+
+/// Enables tracing, writing every future event to the given output.
+pub fn enable(format: Format, output: Box<dyn Write + Send>) {
+    *TRACER.lock().unwrap_or_else(|v| v.into_inner()) = Some(Tracer { format, output });
+
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether tracing has been enabled.
+#[inline(always)]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Writes a single trace event. Use the [`trace!`] macro instead of calling this directly.
+#[cold]
+pub fn emit(module: &str, event: &str, message: Arguments) {
+    let mut tracer = TRACER.lock().unwrap_or_else(|v| v.into_inner());
+    let Some(tracer) = tracer.as_mut() else { return };
+
+    // Every solution lives in a module named after its day, so we can pull the number straight out of the path.
+    let source = module.rsplit("::").next().unwrap_or(module);
+    let day = source.strip_prefix("day_").and_then(|v| v.parse::<u8>().ok());
+
+    // A failing trace shouldn't bring down the solution that it's explaining, so write errors are ignored.
+    _ = match tracer.format {
+        Format::Text => match day {
+            Some(day) => writeln!(tracer.output, "[day {day}] {event}: {message}"),
+            None => writeln!(tracer.output, "[{source}] {event}: {message}"),
+        },
+        Format::Json => {
+            let json = Json::object([
+                ("source", Json::from(source)),
+                ("day", Json::from(day)),
+                ("event", Json::from(event)),
+                ("message", Json::from(message.to_string())),
+            ]);
+
+            writeln!(tracer.output, "{json}")
+        }
+    };
+}
@@ -0,0 +1,389 @@
+//! A from-scratch implementation of the ChaCha20-Poly1305 authenticated cipher, as described by [RFC 8439].
+//!
+//! This exists so that puzzle inputs can be kept in the repository without actually publishing them. It follows the
+//! RFC to the letter and is checked against its test vectors, but it has never been audited by anyone who knows what
+//! they're doing, so please don't go protecting anything important with it.
+//!
+//! [RFC 8439]: https://datatracker.ietf.org/doc/html/rfc8439
+
+use anyhow::{Result, bail};
+
+/// The length of a key, in bytes.
+pub const KEY_LENGTH: usize = 32;
+/// The length of a nonce, in bytes.
+pub const NONCE_LENGTH: usize = 12;
+/// The length of an authentication tag, in bytes.
+pub const TAG_LENGTH: usize = 16;
+
+/// A secret key.
+pub type Key = [u8; KEY_LENGTH];
+/// A number that must never be used more than once with the same key.
+pub type Nonce = [u8; NONCE_LENGTH];
+
+/// Encrypts and authenticates the given plaintext, returning the ciphertext followed by its authentication tag.
+///
+/// The associated data isn't encrypted, but it is authenticated, so [`open`] fails unless it's given the exact same
+/// associated data.
+pub fn seal(key: &Key, nonce: &Nonce, associated: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let mut sealed = plaintext.to_vec();
+
+    self::chacha20_xor(key, 1, nonce, &mut sealed);
+
+    let tag = self::aead_tag(key, nonce, associated, &sealed);
+
+    sealed.extend_from_slice(&tag);
+    sealed
+}
+
+/// Verifies and decrypts data that was previously created using [`seal`], returning the plaintext.
+///
+/// This fails if the key, nonce, or associated data are wrong, or if the sealed data has been tampered with.
+pub fn open(key: &Key, nonce: &Nonce, associated: &[u8], sealed: &[u8]) -> Result<Vec<u8>> {
+    let Some((ciphertext, tag)) = sealed.split_at_checked(sealed.len().wrapping_sub(TAG_LENGTH)) else {
+        bail!("the sealed data is too short to contain an authentication tag");
+    };
+
+    let expected = self::aead_tag(key, nonce, associated, ciphertext);
+
+    // Comparing every byte, rather than stopping at the first difference, avoids leaking how much of the tag was right.
+    if expected.iter().zip(tag).fold(0, |difference, (a, b)| difference | (a ^ b)) != 0 {
+        bail!("either the key is wrong or the sealed data has been tampered with");
+    }
+
+    let mut plaintext = ciphertext.to_vec();
+
+    self::chacha20_xor(key, 1, nonce, &mut plaintext);
+
+    Ok(plaintext)
+}
+
+/// Computes the authentication tag for the given associated data and ciphertext (RFC 8439, section 2.8).
+fn aead_tag(key: &Key, nonce: &Nonce, associated: &[u8], ciphertext: &[u8]) -> [u8; TAG_LENGTH] {
+    let block = self::chacha20_block(key, 0, nonce);
+    // The first half of the very first block is used as a one-time key, which is why encryption starts at block one.
+    let mut poly = Poly1305::new(block[.. 32].try_into().unwrap());
+
+    let padding = |length: usize| &[0; 16][.. (16 - length % 16) % 16];
+
+    poly.update(associated);
+    poly.update(padding(associated.len()));
+    poly.update(ciphertext);
+    poly.update(padding(ciphertext.len()));
+    poly.update(&(associated.len() as u64).to_le_bytes());
+    poly.update(&(ciphertext.len() as u64).to_le_bytes());
+    poly.finish()
+}
+
+/// Encrypts or decrypts the given data in place, starting at the given block counter (RFC 8439, section 2.4).
+fn chacha20_xor(key: &Key, counter: u32, nonce: &Nonce, data: &mut [u8]) {
+    for (index, chunk) in data.chunks_mut(64).enumerate() {
+        let block = self::chacha20_block(key, counter.wrapping_add(index as u32), nonce);
+
+        chunk.iter_mut().zip(block).for_each(|(byte, key)| *byte ^= key);
+    }
+}
+
+/// Generates a single block of the ChaCha20 key stream (RFC 8439, section 2.3).
+fn chacha20_block(key: &Key, counter: u32, nonce: &Nonce) -> [u8; 64] {
+    let word = |bytes: &[u8], index: usize| u32::from_le_bytes(bytes[index * 4 .. index * 4 + 4].try_into().unwrap());
+
+    // The first four words just spell out "expand 32-byte k".
+    let mut initial = [0x6170_7865, 0x3320_646E, 0x7962_2D32, 0x6B20_6574, 0, 0, 0, 0, 0, 0, 0, 0, counter, 0, 0, 0];
+
+    (0 .. 8).for_each(|i| initial[4 + i] = word(key, i));
+    (0 .. 3).for_each(|i| initial[13 + i] = word(nonce, i));
+
+    let mut state = initial;
+
+    for _ in 0 .. 10 {
+        // Columns...
+        self::quarter_round(&mut state, 0, 4, 8, 12);
+        self::quarter_round(&mut state, 1, 5, 9, 13);
+        self::quarter_round(&mut state, 2, 6, 10, 14);
+        self::quarter_round(&mut state, 3, 7, 11, 15);
+        // ...then diagonals.
+        self::quarter_round(&mut state, 0, 5, 10, 15);
+        self::quarter_round(&mut state, 1, 6, 11, 12);
+        self::quarter_round(&mut state, 2, 7, 8, 13);
+        self::quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut block = [0; 64];
+
+    for (index, (word, initial)) in state.into_iter().zip(initial).enumerate() {
+        block[index * 4 .. index * 4 + 4].copy_from_slice(&word.wrapping_add(initial).to_le_bytes());
+    }
+
+    block
+}
+
+/// Applies the ChaCha quarter round to four words of the given state (RFC 8439, section 2.1).
+const fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// The Poly1305 one-time authenticator (RFC 8439, section 2.5).
+///
+/// The 130-bit arithmetic is done using five 26-bit limbs, so that every product comfortably fits within a `u64`.
+#[derive(Clone, Debug)]
+struct Poly1305 {
+    /// The clamped multiplier.
+    r: [u32; 5],
+    /// The accumulator.
+    h: [u32; 5],
+    /// The value added to the accumulator at the very end.
+    s: [u32; 4],
+    /// Bytes that haven't filled up a complete block yet.
+    buffer: Vec<u8>,
+}
+
+impl Poly1305 {
+    /// The mask of a single limb.
+    const MASK: u32 = 0x03FF_FFFF;
+
+    /// Creates a new [`Poly1305`] using the given one-time key.
+    fn new(key: &[u8; 32]) -> Self {
+        let word = |index: usize| u32::from_le_bytes(key[index .. index + 4].try_into().unwrap());
+
+        Self {
+            // This is the clamped `r` from the RFC, already split into limbs.
+            r: [
+                word(0) & 0x03FF_FFFF,
+                (word(3) >> 2) & 0x03FF_FF03,
+                (word(6) >> 4) & 0x03FF_C0FF,
+                (word(9) >> 6) & 0x03F0_3FFF,
+                (word(12) >> 8) & 0x000F_FFFF,
+            ],
+            h: [0; 5],
+            s: [word(16), word(20), word(24), word(28)],
+            buffer: Vec::with_capacity(16),
+        }
+    }
+
+    /// Feeds the given bytes into the authenticator.
+    fn update(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+
+        let complete = self.buffer.len() / 16 * 16;
+        let buffer = std::mem::take(&mut self.buffer);
+
+        for block in buffer[.. complete].chunks_exact(16) {
+            self.block(block.try_into().unwrap(), 1 << 24);
+        }
+
+        self.buffer = buffer[complete ..].to_vec();
+    }
+
+    /// Adds a single block to the accumulator, then multiplies it by `r`.
+    ///
+    /// The high bit is the `1` that's appended to every block, which is already accounted for in partial blocks.
+    fn block(&mut self, block: &[u8; 16], high_bit: u32) {
+        let word = |index: usize| u32::from_le_bytes(block[index .. index + 4].try_into().unwrap());
+        let [r0, r1, r2, r3, r4] = self.r.map(u64::from);
+        let [s1, s2, s3, s4] = [r1 * 5, r2 * 5, r3 * 5, r4 * 5];
+
+        self.h[0] += word(0) & Self::MASK;
+        self.h[1] += (word(3) >> 2) & Self::MASK;
+        self.h[2] += (word(6) >> 4) & Self::MASK;
+        self.h[3] += (word(9) >> 6) & Self::MASK;
+        self.h[4] += (word(12) >> 8) | high_bit;
+
+        let [h0, h1, h2, h3, h4] = self.h.map(u64::from);
+        let mut d = [
+            h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1,
+            h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2,
+            h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3,
+            h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4,
+            h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0,
+        ];
+
+        // Carry everything back down into 26-bit limbs, wrapping the overflow around as `2^130 = 5 (mod p)`.
+        for index in 0 .. 4 {
+            d[index + 1] += d[index] >> 26;
+            d[index] &= u64::from(Self::MASK);
+        }
+
+        self.h = d.map(|v| v as u32);
+        self.h[4] &= Self::MASK;
+        self.h[0] += (d[4] >> 26) as u32 * 5;
+        self.h[1] += self.h[0] >> 26;
+        self.h[0] &= Self::MASK;
+    }
+
+    /// Consumes the authenticator, returning its tag.
+    fn finish(mut self) -> [u8; TAG_LENGTH] {
+        if !self.buffer.is_empty() {
+            let mut block = [0; 16];
+
+            block[.. self.buffer.len()].copy_from_slice(&self.buffer);
+            block[self.buffer.len()] = 1;
+
+            self.block(&block, 0);
+        }
+
+        let mut h = self.h;
+
+        // Fully carry the accumulator...
+        for index in 1 .. 5 {
+            let carry = h[index] >> 26;
+
+            h[index] &= Self::MASK;
+
+            if index < 4 { h[index + 1] += carry } else { h[0] += carry * 5 }
+        }
+
+        h[1] += h[0] >> 26;
+        h[0] &= Self::MASK;
+
+        // ...then compute `h - p`, and use it instead of `h` if it didn't underflow.
+        let mut g = [0; 5];
+        let mut carry = 5;
+
+        for index in 0 .. 4 {
+            g[index] = h[index] + carry;
+            carry = g[index] >> 26;
+            g[index] &= Self::MASK;
+        }
+
+        g[4] = h[4].wrapping_add(carry).wrapping_sub(1 << 26);
+
+        // This is all done with masks rather than branches, so that it takes the same time either way.
+        let use_g = (g[4] >> 31).wrapping_sub(1);
+
+        for index in 0 .. 5 {
+            h[index] = (h[index] & !use_g) | (g[index] & use_g);
+        }
+
+        let words =
+            [h[0] | (h[1] << 26), (h[1] >> 6) | (h[2] << 20), (h[2] >> 12) | (h[3] << 14), (h[3] >> 18) | (h[4] << 8)];
+        let mut tag = [0; TAG_LENGTH];
+        let mut carry = 0;
+
+        for (index, (word, s)) in words.into_iter().zip(self.s).enumerate() {
+            let sum = u64::from(word) + u64::from(s) + carry;
+
+            tag[index * 4 .. index * 4 + 4].copy_from_slice(&(sum as u32).to_le_bytes());
+            carry = sum >> 32;
+        }
+
+        tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decodes a hexadecimal string, ignoring any whitespace within it.
+    fn hex(string: &str) -> Vec<u8> {
+        let digits: Vec<u8> = string.bytes().filter(|v| !v.is_ascii_whitespace()).collect();
+
+        digits.chunks(2).map(|v| u8::from_str_radix(std::str::from_utf8(v).unwrap(), 16).unwrap()).collect()
+    }
+
+    /// Computes the Poly1305 tag of the given message using the given hexadecimal key.
+    fn poly1305(key: &str, message: &[u8]) -> Vec<u8> {
+        let mut poly = Poly1305::new(&hex(key).try_into().unwrap());
+
+        poly.update(message);
+        poly.finish().to_vec()
+    }
+
+    #[test]
+    fn chacha20_block_matches_rfc() {
+        // RFC 8439, section 2.3.2.
+        let key: Key = std::array::from_fn(|i| i as u8);
+        let nonce: Nonce = hex("000000090000004a00000000").try_into().unwrap();
+        let expected = hex("10f1e7e4d13b5915500fdd1fa32071c4 c7d1f4c733c068030422aa9ac3d46c4e
+             d2826446079faa0914c2d705d98b02a2 b5129cd1de164eb9cbd083e8a2503c4e");
+
+        assert_eq!(self::chacha20_block(&key, 1, &nonce).to_vec(), expected);
+    }
+
+    #[test]
+    fn poly1305_matches_rfc() {
+        // RFC 8439, appendix A.3, test vectors 1, 2, 4, 5, 6, 7, 8 and 9.
+        let zeroes = "00000000000000000000000000000000";
+        let one = "01000000000000000000000000000000";
+        let two = "02000000000000000000000000000000";
+        let ones = "ffffffffffffffffffffffffffffffff";
+        let jabberwocky = "'Twas brillig, and the slithy toves\nDid gyre and gimble in the wabe:\nAll mimsy were the \
+                           borogoves,\nAnd the mome raths outgrabe.";
+
+        assert_eq!(self::poly1305(&format!("{zeroes}{zeroes}"), &[0; 64]), hex(zeroes));
+        // With `r` at zero, the tag is just `s` no matter what the message is.
+        assert_eq!(
+            self::poly1305(&format!("{zeroes}36e5f6b5c5e06070f0efca96227a863e"), b"Any submission to the IETF"),
+            hex("36e5f6b5c5e06070f0efca96227a863e")
+        );
+        assert_eq!(
+            self::poly1305("1c9240a5eb55d38af333888604f6b5f0473917c1402b80099dca5cbc207075c0", jabberwocky.as_bytes()),
+            hex("4541669a7eaaee61e708dc7cbcc5eb62")
+        );
+        assert_eq!(self::poly1305(&format!("{two}{zeroes}"), &hex(ones)), hex("03000000000000000000000000000000"));
+        assert_eq!(self::poly1305(&format!("{two}{ones}"), &hex(two)), hex("03000000000000000000000000000000"));
+        assert_eq!(
+            self::poly1305(
+                &format!("{one}{zeroes}"),
+                &hex(&format!("{ones}f0ffffffffffffffffffffffffffffff11{}", &zeroes[2 ..]))
+            ),
+            hex("05000000000000000000000000000000")
+        );
+        assert_eq!(
+            self::poly1305(
+                &format!("{one}{zeroes}"),
+                &hex(&format!("{ones}fbfefefefefefefefefefefefefefefe{}", "01".repeat(16)))
+            ),
+            hex(zeroes)
+        );
+        assert_eq!(
+            self::poly1305(&format!("{two}{zeroes}"), &hex("fdffffffffffffffffffffffffffffff")),
+            hex("faffffffffffffffffffffffffffffff")
+        );
+    }
+
+    #[test]
+    fn aead_matches_rfc() {
+        // RFC 8439, section 2.8.2.
+        let key: Key = std::array::from_fn(|i| 0x80 + i as u8);
+        let nonce: Nonce = hex("070000004041424344454647").try_into().unwrap();
+        let associated = hex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, \
+                          sunscreen would be it.";
+        let expected = hex("d31a8d34648e60db7b86afbc53ef7ec2 a4aded51296e08fea9e2b5a736ee62d6
+             3dbea45e8ca9671282fafb69da92728b 1a71de0a9e060b2905d6a5b67ecd3b36
+             92ddbd7f2d778b8c9803aee328091b58 fab324e4fad675945585808b4831d7bc
+             3ff4def08e4b7a9de576d26586cec64b 6116
+             1ae10b594f09e26a7e902ecbd0600691");
+        let sealed = self::seal(&key, &nonce, &associated, plaintext);
+
+        assert_eq!(sealed, expected);
+        assert_eq!(self::open(&key, &nonce, &associated, &sealed).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn tampering_fails_authentication() {
+        let key: Key = [7; KEY_LENGTH];
+        let nonce: Nonce = [3; NONCE_LENGTH];
+        let sealed = self::seal(&key, &nonce, b"day_1.txt", b"3   4\n4   3\n");
+
+        for index in 0 .. sealed.len() {
+            let mut tampered = sealed.clone();
+
+            tampered[index] ^= 1;
+
+            assert!(self::open(&key, &nonce, b"day_1.txt", &tampered).is_err(), "flipping byte {index} went unnoticed");
+        }
+
+        assert!(self::open(&key, &nonce, b"day_2.txt", &sealed).is_err());
+        assert!(self::open(&[8; KEY_LENGTH], &nonce, b"day_1.txt", &sealed).is_err());
+        assert!(self::open(&key, &nonce, b"day_1.txt", &sealed[.. TAG_LENGTH - 1]).is_err());
+    }
+}
@@ -3,11 +3,14 @@ use std::fmt::{Display, Write};
 use std::hash::Hash;
 use std::iter::FusedIterator;
 use std::num::NonZeroUsize;
-use std::ops::Neg;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 use std::path::Path;
 use std::rc::Rc;
+use std::str::FromStr;
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
+
+use crate::error::Error;
 
 /// A 2D position.
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -63,6 +66,36 @@ impl Pos2D {
         Self::new(self.x(), y)
     }
 
+    /// Moves this position a single step in the given direction.
+    ///
+    /// This will return `None` if either value addition would panic.
+    pub const fn step(self, direction: Direction) -> Option<Self> {
+        self.offset(direction.offset())
+    }
+
+    /// Moves this position a single step in the given direction, so long as it stays within the given size.
+    pub const fn step_within(self, direction: Direction, size: Size2D) -> Option<Self> {
+        self.offset_within(direction.offset(), size)
+    }
+
+    /// Offsets this position in the given direction, so long as it stays within the given size.
+    pub const fn offset_within(self, offset: Offset2D, size: Size2D) -> Option<Self> {
+        match self.offset(offset) {
+            Some(pos) if pos.is_contained_within(size) => Some(pos),
+            _ => None,
+        }
+    }
+
+    /// Offsets this position in the given direction, wrapping around the edges of the given size as if it were a
+    /// torus.
+    pub const fn offset_wrapping(self, offset: Offset2D, size: Size2D) -> Self {
+        // Widening first means that this can't overflow, no matter how far the offset goes.
+        let x = (self.x() as i128 + offset.x() as i128).rem_euclid(size.w().get() as i128);
+        let y = (self.y() as i128 + offset.y() as i128).rem_euclid(size.h().get() as i128);
+
+        Self::new(x as usize, y as usize)
+    }
+
     /// Offsets this position in the given direction.
     ///
     /// This will return `None` if either value addition would panic.
@@ -85,8 +118,104 @@ impl Pos2D {
 
         Some(Self::new(x, y))
     }
+
+    /// Returns an iterator over the positions reached by repeatedly offsetting this one, stopping once they leave the
+    /// given size.
+    ///
+    /// This position itself isn't included, and a zero offset yields nothing rather than this position forever.
+    pub const fn ray(self, offset: Offset2D, size: Size2D) -> Ray {
+        let next = if offset.x() == 0 && offset.y() == 0 { None } else { self.offset_within(offset, size) };
+
+        Ray { next, offset, size }
+    }
+
+    /// Returns an iterator over every position on the line segment from `a` to `b`, including both ends.
+    ///
+    /// Only positions that sit exactly on the line are included, so a segment from `(0, 0)` to `(4, 2)` visits
+    /// `(2, 1)` but not `(1, 0)`.
+    pub fn line_between(a: Self, b: Self) -> impl Iterator<Item = Self> {
+        let step = (b - a).normalized();
+        let count = match step.chebyshev_length() {
+            0 => 1,
+            length => (b - a).chebyshev_length() / length + 1,
+        };
+
+        std::iter::successors(Some(a), move |v| *v + step).take(count)
+    }
+
+    /// Returns the offset that would move this position onto the other one.
+    ///
+    /// This is also available as `other - self`.
+    pub const fn offset_to(self, other: Self) -> Offset2D {
+        Offset2D::new(other.x() as isize - self.x() as isize, other.y() as isize - self.y() as isize)
+    }
+
+    /// Returns the number of orthogonal steps between this position and the other one.
+    pub const fn manhattan_distance(self, other: Self) -> usize {
+        self.x().abs_diff(other.x()) + self.y().abs_diff(other.y())
+    }
+
+    /// Returns the number of steps between this position and the other one, if diagonal steps are allowed too.
+    pub const fn chebyshev_distance(self, other: Self) -> usize {
+        let x = self.x().abs_diff(other.x());
+        let y = self.y().abs_diff(other.y());
+
+        if x > y { x } else { y }
+    }
+}
+
+impl Sub for Pos2D {
+    type Output = Offset2D;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        rhs.offset_to(self)
+    }
 }
 
+// Positions can't go negative, so moving one around has to be checked rather than panicking halfway through a puzzle.
+impl Add<Offset2D> for Pos2D {
+    type Output = Option<Self>;
+
+    fn add(self, rhs: Offset2D) -> Self::Output {
+        self.offset(rhs)
+    }
+}
+
+impl Sub<Offset2D> for Pos2D {
+    type Output = Option<Self>;
+
+    fn sub(self, rhs: Offset2D) -> Self::Output {
+        self.offset(-rhs)
+    }
+}
+
+/// An iterator over the positions reached by repeatedly offsetting a position, stopping once they leave a size.
+///
+/// This is created by [`Pos2D::ray`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ray {
+    /// The position that will be returned next, if it's still within the size.
+    next: Option<Pos2D>,
+    /// The offset applied at every step.
+    offset: Offset2D,
+    /// The size that the ray stays within.
+    size: Size2D,
+}
+
+impl Iterator for Ray {
+    type Item = Pos2D;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.next?;
+
+        self.next = pos.offset_within(self.offset, self.size);
+
+        Some(pos)
+    }
+}
+
+impl FusedIterator for Ray {}
+
 /// A 2D position.
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Offset2D {
@@ -135,9 +264,55 @@ impl Offset2D {
         Self::new(self.x() / by, self.y() / by)
     }
 
-    /// Returns an iterator over each direction offset.
-    pub fn directions() -> impl Iterator<Item = Self> {
-        (-1 ..= 1).flat_map(|y| (-1 ..= 1).map(move |x| Self::new(x, y)))
+    /// Returns the number of orthogonal steps that this offset moves.
+    pub const fn manhattan_length(self) -> usize {
+        self.x().unsigned_abs() + self.y().unsigned_abs()
+    }
+
+    /// Returns the number of steps that this offset moves, if diagonal steps are allowed too.
+    pub const fn chebyshev_length(self) -> usize {
+        let x = self.x().unsigned_abs();
+        let y = self.y().unsigned_abs();
+
+        if x > y { x } else { y }
+    }
+
+    /// Returns the smallest offset that points in exactly the same direction, by dividing out the greatest common
+    /// divisor of both values.
+    ///
+    /// This is the step that lands on every grid position along this offset's line, rather than skipping some. A zero
+    /// offset has no direction, and is returned as-is.
+    pub const fn normalized(self) -> Self {
+        // Good old Euclid.
+        let mut a = self.x().unsigned_abs();
+        let mut b = self.y().unsigned_abs();
+
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+
+        if a == 0 { self } else { self.divide(a as isize) }
+    }
+
+    /// Rotates this offset clockwise by the given number of quarter turns, where negative values turn anticlockwise.
+    pub const fn rotate(self, quarters: isize) -> Self {
+        // The Y axis points downwards, so this is clockwise on screen despite looking backwards on paper.
+        match quarters.rem_euclid(4) {
+            0 => self,
+            1 => Self::new(-self.y(), self.x()),
+            2 => Self::new(-self.x(), -self.y()),
+            _ => Self::new(self.y(), -self.x()),
+        }
+    }
+
+    /// Rotates this offset clockwise by a quarter turn.
+    pub const fn rotate_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Rotates this offset anticlockwise by a quarter turn.
+    pub const fn rotate_left(self) -> Self {
+        self.rotate(-1)
     }
 }
 
@@ -149,6 +324,246 @@ impl Neg for Offset2D {
     }
 }
 
+impl Add for Offset2D {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.combine(rhs)
+    }
+}
+
+impl AddAssign for Offset2D {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Offset2D {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.combine(-rhs)
+    }
+}
+
+impl SubAssign for Offset2D {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<isize> for Offset2D {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        self.multiply(rhs)
+    }
+}
+
+/// One of the eight directions that can be moved in on a grid.
+///
+/// The Y axis points downwards, so [`Direction::North`] is towards the first row. Variants are ordered clockwise,
+/// starting from north.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, in clockwise order starting from north.
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+    /// The four cardinal directions, in clockwise order starting from north.
+    pub const CARDINAL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+    /// The four ordinal (diagonal) directions, in clockwise order starting from north-east.
+    pub const ORDINAL: [Self; 4] = [Self::NorthEast, Self::SouthEast, Self::SouthWest, Self::NorthWest];
+
+    /// Returns an iterator over every direction, which covers all 8 neighbours of a position.
+    pub fn all() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns an iterator over the cardinal directions, which covers the 4 orthogonal neighbours of a position.
+    pub fn cardinal() -> impl Iterator<Item = Self> {
+        Self::CARDINAL.into_iter()
+    }
+
+    /// Returns an iterator over the ordinal directions, which covers the 4 diagonal neighbours of a position.
+    pub fn ordinal() -> impl Iterator<Item = Self> {
+        Self::ORDINAL.into_iter()
+    }
+
+    /// Returns whether this is one of the four cardinal directions.
+    pub const fn is_cardinal(self) -> bool {
+        (self as u8).is_multiple_of(2)
+    }
+
+    /// Returns whether this is one of the four ordinal (diagonal) directions.
+    pub const fn is_ordinal(self) -> bool {
+        !self.is_cardinal()
+    }
+
+    /// Rotates this direction clockwise by the given number of eighths of a turn, or anticlockwise if it's negative.
+    pub const fn rotate(self, eighths: isize) -> Self {
+        Self::ALL[(self as isize + eighths).rem_euclid(8) as usize]
+    }
+
+    /// Returns the direction a quarter turn clockwise of this one.
+    pub const fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Returns the direction a quarter turn anticlockwise of this one.
+    pub const fn turn_left(self) -> Self {
+        self.rotate(-2)
+    }
+
+    /// Returns the opposite direction.
+    pub const fn turn_around(self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Returns the offset of a single step in this direction.
+    pub const fn offset(self) -> Offset2D {
+        match self {
+            Self::North => Offset2D::new(0, -1),
+            Self::NorthEast => Offset2D::new(1, -1),
+            Self::East => Offset2D::new(1, 0),
+            Self::SouthEast => Offset2D::new(1, 1),
+            Self::South => Offset2D::new(0, 1),
+            Self::SouthWest => Offset2D::new(-1, 1),
+            Self::West => Offset2D::new(-1, 0),
+            Self::NorthWest => Offset2D::new(-1, -1),
+        }
+    }
+
+    /// Returns the direction of a single step with the given offset.
+    ///
+    /// This will return `None` if the offset isn't exactly one step in any direction.
+    pub const fn from_offset(offset: Offset2D) -> Option<Self> {
+        Some(match (offset.x(), offset.y()) {
+            (0, -1) => Self::North,
+            (1, -1) => Self::NorthEast,
+            (1, 0) => Self::East,
+            (1, 1) => Self::SouthEast,
+            (0, 1) => Self::South,
+            (-1, 1) => Self::SouthWest,
+            (-1, 0) => Self::West,
+            (-1, -1) => Self::NorthWest,
+            _ => return None,
+        })
+    }
+
+    /// Returns the arrow pointing in this direction.
+    ///
+    /// The cardinal directions use the usual ASCII arrows (`^>v<`), but there's no such thing for the diagonals.
+    pub const fn to_char(self) -> char {
+        match self {
+            Self::North => '^',
+            Self::NorthEast => '↗',
+            Self::East => '>',
+            Self::SouthEast => '↘',
+            Self::South => 'v',
+            Self::SouthWest => '↙',
+            Self::West => '<',
+            Self::NorthWest => '↖',
+        }
+    }
+
+    /// Returns the direction that the given character represents, either as an arrow or as a compass letter.
+    pub const fn from_char(character: char) -> Option<Self> {
+        Some(match character {
+            '^' | 'N' | 'n' => Self::North,
+            '↗' => Self::NorthEast,
+            '>' | 'E' | 'e' => Self::East,
+            '↘' => Self::SouthEast,
+            'v' | 'S' | 's' => Self::South,
+            '↙' => Self::SouthWest,
+            '<' | 'W' | 'w' => Self::West,
+            '↖' => Self::NorthWest,
+            _ => return None,
+        })
+    }
+
+    /// Returns this direction's compass abbreviation.
+    pub const fn abbreviation(self) -> &'static str {
+        match self {
+            Self::North => "N",
+            Self::NorthEast => "NE",
+            Self::East => "E",
+            Self::SouthEast => "SE",
+            Self::South => "S",
+            Self::SouthWest => "SW",
+            Self::West => "W",
+            Self::NorthWest => "NW",
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.abbreviation())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut characters = s.chars();
+
+        if let (Some(character), None) = (characters.next(), characters.next())
+            && let Some(direction) = Self::from_char(character)
+        {
+            return Ok(direction);
+        }
+
+        match Self::all().find(|v| v.abbreviation().eq_ignore_ascii_case(s)) {
+            Some(direction) => Ok(direction),
+            None => bail!("invalid direction '{s}', expected an arrow (like '^') or a compass direction (like 'NE')"),
+        }
+    }
+}
+
+/// Which of the positions around a position count as its neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity<'s> {
+    /// The four positions that share an edge, in clockwise order starting from north.
+    Four,
+    /// All eight surrounding positions, in clockwise order starting from north.
+    Eight,
+    /// Any other set of offsets, such as the moves of a knight.
+    Stencil(&'s [Offset2D]),
+}
+
+impl<'s> Connectivity<'s> {
+    /// Returns an iterator over the offset of every neighbour.
+    pub fn offsets(self) -> impl Iterator<Item = Offset2D> + 's {
+        let (directions, stencil): (&[Direction], &[Offset2D]) = match self {
+            Self::Four => (&Direction::CARDINAL, &[]),
+            Self::Eight => (&Direction::ALL, &[]),
+            Self::Stencil(offsets) => (&[], offsets),
+        };
+
+        directions.iter().map(|v| v.offset()).chain(stencil.iter().copied())
+    }
+}
+
 /// A 2D grid size.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Size2D {
@@ -214,10 +629,39 @@ impl Size2D {
     pub fn positions(&self) -> impl Iterator<Item = Pos2D> {
         (0 .. self.h().get()).flat_map(|y| (0 .. self.w().get()).map(move |x| Pos2D::new(x, y)))
     }
+
+    /// Returns an iterator over the neighbours of the given position, skipping any outside of this size.
+    pub fn neighbors<'s>(self, pos: Pos2D, connectivity: Connectivity<'s>) -> impl Iterator<Item = Pos2D> + 's {
+        connectivity.offsets().filter_map(move |v| pos.offset_within(v, self))
+    }
+
+    /// Returns an iterator over the neighbours of the given position, wrapping around the edges of this size as if it
+    /// were a torus.
+    ///
+    /// Sizes smaller than the stencil will yield some positions more than once, and possibly the given one too.
+    pub fn wrapping_neighbors<'s>(
+        self,
+        pos: Pos2D,
+        connectivity: Connectivity<'s>,
+    ) -> impl Iterator<Item = Pos2D> + 's {
+        connectivity.offsets().map(move |v| pos.offset_wrapping(v, self))
+    }
+
+    /// Returns an iterator over every position within this size that sits on the infinite line through `a` and `b`,
+    /// in order from one edge to the other.
+    ///
+    /// Nothing is returned if `a` itself is outside of this size, and just `a` is returned if both are the same.
+    pub fn line_through(self, a: Pos2D, b: Pos2D) -> impl Iterator<Item = Pos2D> {
+        let step = (b - a).normalized();
+        // Walk backwards to the edge first, so that the line can be returned in a single direction.
+        let start = a.is_contained_within(self).then(|| a.ray(-step, self).last().unwrap_or(a));
+
+        start.into_iter().flat_map(move |v| std::iter::once(v).chain(v.ray(step, self)))
+    }
 }
 
 /// A 2D grid containing values of type `T`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Grid2D<T> {
     /// The grid's size.
     size: Size2D,
@@ -251,6 +695,119 @@ impl<T> Grid2D<T> {
         _ = self.cells[index].insert(value);
     }
 
+    /// Returns an iterator over the neighbours of the given position, skipping any outside of the grid.
+    pub fn neighbors<'s>(&self, pos: Pos2D, connectivity: Connectivity<'s>) -> impl Iterator<Item = Pos2D> + 's {
+        self.size.neighbors(pos, connectivity)
+    }
+
+    /// Returns an iterator over the neighbours of the given position and their values, skipping any outside of the
+    /// grid.
+    pub fn neighbors_with_values<'g>(
+        &'g self,
+        pos: Pos2D,
+        connectivity: Connectivity<'g>,
+    ) -> impl Iterator<Item = (Pos2D, Option<&'g T>)> {
+        self.neighbors(pos, connectivity).map(|v| (v, self.get(v)))
+    }
+
+    /// Returns an iterator over the neighbours of the given position, wrapping around the edges of the grid as if it
+    /// were a torus.
+    pub fn wrapping_neighbors<'s>(
+        &self,
+        pos: Pos2D,
+        connectivity: Connectivity<'s>,
+    ) -> impl Iterator<Item = Pos2D> + 's {
+        self.size.wrapping_neighbors(pos, connectivity)
+    }
+
+    /// Returns an iterator over the neighbours of the given position and their values, wrapping around the edges of
+    /// the grid as if it were a torus.
+    pub fn wrapping_neighbors_with_values<'g>(
+        &'g self,
+        pos: Pos2D,
+        connectivity: Connectivity<'g>,
+    ) -> impl Iterator<Item = (Pos2D, Option<&'g T>)> {
+        self.wrapping_neighbors(pos, connectivity).map(|v| (v, self.get(v)))
+    }
+
+    /// Returns an iterator over the positions and values reached by repeatedly offsetting the given position, stopping
+    /// at the edge of the grid.
+    ///
+    /// Just like [`Pos2D::ray`], the given position itself isn't included.
+    pub fn ray_values(&self, pos: Pos2D, offset: Offset2D) -> impl Iterator<Item = (Pos2D, Option<&T>)> {
+        pos.ray(offset, self.size).map(|v| (v, self.get(v)))
+    }
+
+    /// Returns the cells of the row at the given Y value, from left to right.
+    pub fn row(&self, y: usize) -> Option<&[Option<T>]> {
+        self.rows().nth(y)
+    }
+
+    /// Returns an iterator over the cells of the column at the given X value, from top to bottom.
+    pub fn column(&self, x: usize) -> Option<impl Clone + ExactSizeIterator<Item = &Option<T>>> {
+        (x < self.size.w().get()).then(|| self.cells[x ..].iter().step_by(self.size.w().get()))
+    }
+
+    /// Returns an iterator over every row of the grid, from top to bottom.
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, Option<T>> {
+        self.cells.chunks_exact(self.size.w().get())
+    }
+
+    /// Returns an iterator over every column of the grid, from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Clone + ExactSizeIterator<Item = &Option<T>>> {
+        (0 .. self.size.w().get()).flat_map(|x| self.column(x))
+    }
+
+    /// Returns an iterator over every diagonal of the grid that runs from the top left towards the bottom right.
+    ///
+    /// These start from the bottom left corner and end at the top right one, so the first and last are just a single
+    /// cell each.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Clone + Iterator<Item = &Option<T>>> {
+        let (w, h) = (self.size.w().get(), self.size.h().get());
+        let starts = (0 .. h).rev().map(|y| Pos2D::new(0, y)).chain((1 .. w).map(|x| Pos2D::new(x, 0)));
+
+        starts.map(|v| self.line_cells(v, Direction::SouthEast))
+    }
+
+    /// Returns an iterator over every diagonal of the grid that runs from the top right towards the bottom left.
+    ///
+    /// These start from the top left corner and end at the bottom right one, so the first and last are just a single
+    /// cell each.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Clone + Iterator<Item = &Option<T>>> {
+        let (w, h) = (self.size.w().get(), self.size.h().get());
+        let starts = (0 .. w).map(|x| Pos2D::new(x, 0)).chain((1 .. h).map(move |y| Pos2D::new(w - 1, y)));
+
+        starts.map(|v| self.line_cells(v, Direction::SouthWest))
+    }
+
+    /// Returns an iterator over the cells from the given position up to the edge of the grid in the given direction.
+    fn line_cells(&self, start: Pos2D, direction: Direction) -> impl Clone + Iterator<Item = &Option<T>> {
+        let positions = std::iter::once(start).chain(start.ray(direction.offset(), self.size));
+
+        // Every position is already within the grid, so none of these can fail.
+        positions.filter_map(|v| v.into_index(self.size)).map(|i| &self.cells[i])
+    }
+
+    /// Returns a view of the whole grid as if it had been transformed into the given orientation, without copying it.
+    pub const fn view(&self, orientation: Orientation) -> GridView<'_, T> {
+        GridView { grid: self, origin: Pos2D::new(0, 0), size: self.size, orientation }
+    }
+
+    /// Returns a view of the window with the given top left corner and size, without copying it.
+    ///
+    /// This will return `None` if the window doesn't fit entirely within the grid.
+    pub const fn window(&self, origin: Pos2D, size: Size2D) -> Option<GridView<'_, T>> {
+        let right = origin.x().checked_add(size.w().get());
+        let bottom = origin.y().checked_add(size.h().get());
+
+        match (right, bottom) {
+            (Some(right), Some(bottom)) if right <= self.size.w().get() && bottom <= self.size.h().get() => {
+                Some(GridView { grid: self, origin, size, orientation: Orientation::IDENTITY })
+            }
+            _ => None,
+        }
+    }
+
     /// Removes the value from the given position.
     pub fn remove(&mut self, pos: Pos2D) {
         let Some(index) = pos.into_index(self.size) else { return };
@@ -269,12 +826,12 @@ impl<T> Grid2D<T> {
     }
 
     /// Returns an iterator over references to the cells of this grid.
-    pub fn cells(&self) -> std::slice::Iter<Option<T>> {
+    pub fn cells(&self) -> std::slice::Iter<'_, Option<T>> {
         self.cells.iter()
     }
 
     /// Returns an iterator over references to the cells of this grid.
-    pub fn cells_mut(&mut self) -> std::slice::IterMut<Option<T>> {
+    pub fn cells_mut(&mut self) -> std::slice::IterMut<'_, Option<T>> {
         self.cells.iter_mut()
     }
 
@@ -303,6 +860,471 @@ impl<T> Grid2D<T> {
     }
 }
 
+impl<T> Grid2D<T> {
+    /// Draws this grid as text, one row per line, using the given function to pick the character for every cell.
+    pub fn render_with<F>(&self, render: F) -> String
+    where
+        F: FnMut(Option<&T>) -> char,
+    {
+        let mut output = String::with_capacity(self.size.w().get() * (self.size.h().get() + 1));
+
+        // Writing into a string never fails.
+        _ = self.write_with(&mut output, render);
+
+        output
+    }
+
+    /// Writes this grid as text, one row per line, using the given function to pick the character for every cell.
+    fn write_with<W, F>(&self, f: &mut W, mut render: F) -> std::fmt::Result
+    where
+        W: Write,
+        F: FnMut(Option<&T>) -> char,
+    {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                f.write_char('\n')?;
+            }
+
+            for cell in row {
+                f.write_char(render(cell.as_ref()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Tile> Grid2D<T> {
+    /// The character that empty cells are drawn as, and that character grids are parsed from.
+    pub const EMPTY_CHAR: char = '.';
+
+    /// Draws this grid as text, one row per line, drawing empty cells as the given character.
+    pub fn render(&self, empty: char) -> String {
+        self.render_with(|v| v.map_or(empty, Tile::to_char))
+    }
+}
+
+/// Draws the grid one row per line using each tile's character, with empty cells drawn as [`Grid2D::EMPTY_CHAR`].
+impl<T: Tile> Display for Grid2D<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_with(f, |v| v.map_or(Self::EMPTY_CHAR, Tile::to_char))
+    }
+}
+
+/// Parses a grid from text, with every [`Grid2D::EMPTY_CHAR`] becoming an empty cell.
+///
+/// Parsing a displayed grid always gives back an equal one, so long as none of its cells contain the empty character
+/// or a line break.
+impl FromStr for Grid2D<char> {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        self::string_to_char_grid(s, |_, c| (c != Self::EMPTY_CHAR).then_some(c))
+    }
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// Returns a copy of this grid with its rows and columns swapped, mirroring it along the main diagonal.
+    pub fn transpose(&self) -> Self {
+        self.view(Orientation::TRANSPOSE).to_grid()
+    }
+
+    /// Returns a copy of this grid rotated a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        self.view(Orientation::ROTATE_CW).to_grid()
+    }
+
+    /// Returns a copy of this grid rotated a quarter turn anticlockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        self.view(Orientation::ROTATE_CCW).to_grid()
+    }
+
+    /// Returns a copy of this grid mirrored from left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        self.view(Orientation::FLIP_HORIZONTAL).to_grid()
+    }
+
+    /// Returns a copy of this grid mirrored from top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        self.view(Orientation::FLIP_VERTICAL).to_grid()
+    }
+
+    /// Returns a copy of the window with the given top left corner and size.
+    ///
+    /// This will return `None` if the window doesn't fit entirely within the grid.
+    pub fn subgrid(&self, origin: Pos2D, size: Size2D) -> Option<Self> {
+        self.window(origin, size).map(|v| v.to_grid())
+    }
+}
+
+/// One of the eight ways that a grid can be rotated and mirrored.
+///
+/// Grids are mirrored from left to right first (if at all), and then rotated clockwise.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Orientation {
+    /// The number of clockwise quarter turns, from 0 to 3.
+    quarters: u8,
+    /// Whether the grid is mirrored from left to right before it's rotated.
+    flipped: bool,
+}
+
+impl Orientation {
+    /// Every orientation, with the four rotations first.
+    pub const ALL: [Self; 8] = [
+        Self::IDENTITY,
+        Self::ROTATE_CW,
+        Self::ROTATE_HALF,
+        Self::ROTATE_CCW,
+        Self::FLIP_HORIZONTAL,
+        Self::ANTI_TRANSPOSE,
+        Self::FLIP_VERTICAL,
+        Self::TRANSPOSE,
+    ];
+    /// Mirrors the grid along its anti-diagonal, which runs from the top right to the bottom left.
+    pub const ANTI_TRANSPOSE: Self = Self::new(1, true);
+    /// Mirrors the grid from left to right.
+    pub const FLIP_HORIZONTAL: Self = Self::new(0, true);
+    /// Mirrors the grid from top to bottom.
+    pub const FLIP_VERTICAL: Self = Self::new(2, true);
+    /// Leaves the grid exactly as it is.
+    pub const IDENTITY: Self = Self::new(0, false);
+    /// Rotates the grid a quarter turn anticlockwise.
+    pub const ROTATE_CCW: Self = Self::new(3, false);
+    /// Rotates the grid a quarter turn clockwise.
+    pub const ROTATE_CW: Self = Self::new(1, false);
+    /// Rotates the grid a half turn.
+    pub const ROTATE_HALF: Self = Self::new(2, false);
+    /// Mirrors the grid along its main diagonal, swapping rows and columns.
+    pub const TRANSPOSE: Self = Self::new(3, true);
+
+    /// Creates a new [`Orientation`] from a number of clockwise quarter turns, applied after an optional left to right
+    /// mirroring.
+    pub const fn new(quarters: u8, flipped: bool) -> Self {
+        Self { quarters: quarters % 4, flipped }
+    }
+
+    /// Returns the number of clockwise quarter turns, from 0 to 3.
+    pub const fn quarters(self) -> u8 {
+        self.quarters
+    }
+
+    /// Returns whether the grid is mirrored from left to right before it's rotated.
+    pub const fn is_flipped(self) -> bool {
+        self.flipped
+    }
+
+    /// Returns the size of a grid of the given size once it's in this orientation.
+    pub const fn size(self, size: Size2D) -> Size2D {
+        if self.quarters.is_multiple_of(2) { size } else { Size2D::new(size.h(), size.w()) }
+    }
+
+    /// Returns the position within an untransformed grid of the given size that ends up at the given position once
+    /// it's in this orientation.
+    pub const fn source(self, pos: Pos2D, size: Size2D) -> Pos2D {
+        let (w, h) = (size.w().get(), size.h().get());
+        let (x, y) = (pos.x(), pos.y());
+        let (x, y) = match self.quarters {
+            0 => (x, y),
+            1 => (y, h - 1 - x),
+            2 => (w - 1 - x, h - 1 - y),
+            _ => (w - 1 - y, x),
+        };
+
+        if self.flipped { Pos2D::new(w - 1 - x, y) } else { Pos2D::new(x, y) }
+    }
+}
+
+impl Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            Self::IDENTITY => "identity",
+            Self::ROTATE_CW => "rotated clockwise",
+            Self::ROTATE_HALF => "rotated a half turn",
+            Self::ROTATE_CCW => "rotated anticlockwise",
+            Self::FLIP_HORIZONTAL => "flipped horizontally",
+            Self::ANTI_TRANSPOSE => "anti-transposed",
+            Self::FLIP_VERTICAL => "flipped vertically",
+            _ => "transposed",
+        })
+    }
+}
+
+/// A borrowed window of a [`Grid2D<T>`], seen in any [`Orientation`] without copying any of its cells.
+#[derive(Debug)]
+pub struct GridView<'g, T> {
+    /// The grid being viewed.
+    grid: &'g Grid2D<T>,
+    /// The top left corner of the window within the grid.
+    origin: Pos2D,
+    /// The size of the window within the grid, before it's transformed.
+    size: Size2D,
+    /// How the window is transformed.
+    orientation: Orientation,
+}
+
+// Deriving these would needlessly require `T` to implement them too.
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'g, T> GridView<'g, T> {
+    /// Returns this view's size, after it's been transformed.
+    pub const fn size(self) -> Size2D {
+        self.orientation.size(self.size)
+    }
+
+    /// Returns this view's orientation.
+    pub const fn orientation(self) -> Orientation {
+        self.orientation
+    }
+
+    /// Returns the same window of the grid, seen in the given orientation instead.
+    pub const fn oriented(self, orientation: Orientation) -> Self {
+        Self { orientation, ..self }
+    }
+
+    /// Returns the position within the underlying grid that the given position of this view shows.
+    pub const fn source(self, pos: Pos2D) -> Option<Pos2D> {
+        if !self.size().contains_position(pos) {
+            return None;
+        }
+
+        let pos = self.orientation.source(pos, self.size);
+
+        Some(Pos2D::new(pos.x() + self.origin.x(), pos.y() + self.origin.y()))
+    }
+
+    /// Returns a reference to the value at the given position of this view.
+    pub fn get(self, pos: Pos2D) -> Option<&'g T> {
+        self.grid.get(self.source(pos)?)
+    }
+
+    /// Returns an iterator over the positions of this view and their values, row by row.
+    pub fn iter(self) -> impl Iterator<Item = (Pos2D, Option<&'g T>)> {
+        let (w, h) = (self.size().w().get(), self.size().h().get());
+
+        (0 .. h).flat_map(move |y| (0 .. w).map(move |x| Pos2D::new(x, y))).map(move |v| (v, self.get(v)))
+    }
+
+    /// Copies this view into a new grid.
+    pub fn to_grid(self) -> Grid2D<T>
+    where
+        T: Clone,
+    {
+        let cells = self.iter().map(|(_, v)| v.cloned()).collect();
+
+        Grid2D { size: self.size(), cells }
+    }
+}
+
+/// A small rectangle of values to look for within a grid, where every empty cell matches anything.
+#[derive(Clone, Debug)]
+pub struct GridPattern<T> {
+    /// The values to match, with empty cells acting as wildcards.
+    cells: Grid2D<T>,
+}
+
+impl<T> GridPattern<T> {
+    /// Creates a new [`GridPattern<T>`] from the given grid, treating every empty cell as a wildcard.
+    pub const fn new(cells: Grid2D<T>) -> Self {
+        Self { cells }
+    }
+
+    /// Returns the pattern's size, before it's transformed.
+    pub const fn size(&self) -> Size2D {
+        self.cells.size()
+    }
+}
+
+/// Parses a pattern from an ASCII template, where every `.` matches anything.
+impl FromStr for GridPattern<char> {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = self::string_to_char_grid(s, |_, c| (c != '.').then_some(c)).context("invalid grid pattern")?;
+
+        Ok(Self::new(cells))
+    }
+}
+
+impl<T: PartialEq> Grid2D<T> {
+    /// Returns an iterator over every place that the given pattern matches this grid, in any of the given
+    /// orientations.
+    ///
+    /// Each match is the top left corner of the transformed pattern, along with the orientation that it was found in.
+    /// Orientations that make the pattern look exactly like an earlier one are skipped, so symmetric patterns are only
+    /// ever matched once per place.
+    pub fn find_pattern<'g>(
+        &'g self,
+        pattern: &'g GridPattern<T>,
+        orientations: &[Orientation],
+    ) -> impl Iterator<Item = (Pos2D, Orientation)> + 'g {
+        let mut shapes = Vec::new();
+
+        for &orientation in orientations {
+            let view = pattern.cells.view(orientation);
+            // Wildcards match anything, so there's no need to check them at all.
+            let cells: Vec<_> = view.iter().filter_map(|(p, v)| v.map(|v| (p, v))).collect();
+
+            if shapes.iter().all(|(_, size, other)| (*size, other) != (view.size(), &cells)) {
+                shapes.push((orientation, view.size(), cells));
+            }
+        }
+
+        shapes.into_iter().flat_map(move |(orientation, size, cells)| {
+            // Patterns that are larger than the grid simply end up with nowhere to go.
+            let w = (self.size.w().get() + 1).saturating_sub(size.w().get());
+            let h = (self.size.h().get() + 1).saturating_sub(size.h().get());
+            let origins = (0 .. h).flat_map(move |y| (0 .. w).map(move |x| Pos2D::new(x, y)));
+
+            origins
+                .filter(move |origin| {
+                    cells.iter().all(|(p, v)| self.get(Pos2D::new(origin.x() + p.x(), origin.y() + p.y())) == Some(*v))
+                })
+                .map(move |v| (v, orientation))
+        })
+    }
+}
+
+/// A 2D grid where every cell holds a value of type `T`.
+///
+/// Unlike [`Grid2D<T>`], cells can never be empty, so nothing is spent on telling them apart. This suits things like
+/// maps of numbers, or flags tracking which positions have been visited.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DenseGrid2D<T> {
+    /// The grid's size.
+    size: Size2D,
+    /// The grid's inner cells.
+    cells: Box<[T]>,
+}
+
+impl<T> DenseGrid2D<T> {
+    /// Creates a new [`DenseGrid2D<T>`] with every cell set to the given value.
+    pub fn new(size: Size2D, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let capacity = size.w().get() * size.h().get();
+
+        Self { size, cells: vec![fill; capacity].into_boxed_slice() }
+    }
+
+    /// Creates a new [`DenseGrid2D<T>`], calling the given function to get the value of every cell.
+    pub fn from_fn<F>(size: Size2D, create: F) -> Self
+    where
+        F: FnMut(Pos2D) -> T,
+    {
+        Self { size, cells: size.positions().map(create).collect() }
+    }
+
+    /// Creates a new [`DenseGrid2D<T>`] from the given grid, filling every empty cell with the given value.
+    pub fn from_grid(grid: Grid2D<T>, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self { size: grid.size(), cells: grid.into_cells().map(|v| v.unwrap_or_else(|| fill.clone())).collect() }
+    }
+
+    /// Returns the grid's size.
+    pub const fn size(&self) -> Size2D {
+        self.size
+    }
+
+    /// Returns a reference to the value at the given position.
+    pub fn get(&self, pos: Pos2D) -> Option<&T> {
+        self.cells.get(pos.into_index(self.size)?)
+    }
+
+    /// Returns a mutable reference to the value at the given position.
+    pub fn get_mut(&mut self, pos: Pos2D) -> Option<&mut T> {
+        self.cells.get_mut(pos.into_index(self.size)?)
+    }
+
+    /// Sets the value at the given position, returning the value that was there before.
+    pub fn set(&mut self, pos: Pos2D, value: T) -> Option<T> {
+        self.get_mut(pos).map(|v| std::mem::replace(v, value))
+    }
+
+    /// Returns the total number of cells in the grid.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Always returns `false`, since grids have at least one cell and none of them can be empty.
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns an iterator over references to the cells of this grid.
+    pub fn cells(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// Returns an iterator over references to the cells of this grid.
+    pub fn cells_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Returns an iterator over every row of the grid, from top to bottom.
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, T> {
+        self.cells.chunks_exact(self.size.w().get())
+    }
+
+    /// Returns an iterator over references to the cells of this grid and their positions.
+    pub fn iter(&self) -> impl Clone + Iterator<Item = (Pos2D, &T)> {
+        self.cells().enumerate().map(|(i, v)| (Pos2D::from_index(self.size, i).unwrap(), v))
+    }
+
+    /// Returns an iterator over references to the cells of this grid and their positions.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos2D, &mut T)> {
+        let size = self.size;
+
+        self.cells_mut().enumerate().map(move |(i, v)| (Pos2D::from_index(size, i).unwrap(), v))
+    }
+
+    /// Converts this grid into a [`Grid2D<T>`], where every cell is filled.
+    pub fn into_grid(self) -> Grid2D<T> {
+        Grid2D { size: self.size, cells: self.cells.into_iter().map(Some).collect() }
+    }
+
+    /// Returns the index of the given position, panicking if it's outside of the grid.
+    fn index_of(&self, pos: Pos2D) -> usize {
+        let Some(index) = pos.into_index(self.size) else {
+            let (w, h) = (self.size.w(), self.size.h());
+
+            panic!("position ({}, {}) is outside of the grid's {w}x{h} size", pos.x(), pos.y());
+        };
+
+        index
+    }
+}
+
+impl<T> Index<Pos2D> for DenseGrid2D<T> {
+    type Output = T;
+
+    fn index(&self, index: Pos2D) -> &Self::Output {
+        &self.cells[self.index_of(index)]
+    }
+}
+
+impl<T> IndexMut<Pos2D> for DenseGrid2D<T> {
+    fn index_mut(&mut self, index: Pos2D) -> &mut Self::Output {
+        let index = self.index_of(index);
+
+        &mut self.cells[index]
+    }
+}
+
+impl<T> From<DenseGrid2D<T>> for Grid2D<T> {
+    fn from(value: DenseGrid2D<T>) -> Self {
+        value.into_grid()
+    }
+}
+
 /// A 2D grid containing values of type `T`.
 ///
 /// This type attempts to save memory by avoiding storing duplicate values, instead storing
@@ -324,7 +1346,7 @@ where
     /// Creates a new [`MappedGrid2D<T>`].
     pub fn new(size: Size2D) -> Self {
         let capacity = size.w().get() * size.h().get();
-        let cells = std::iter::repeat(None).take(capacity).collect();
+        let cells = std::iter::repeat_n(None, capacity).collect();
 
         Self { size, cells, values: HashSet::new() }
     }
@@ -369,12 +1391,12 @@ where
     }
 
     /// Returns an iterator over references to the cells of this grid.
-    pub fn cells(&self) -> std::slice::Iter<Option<Rc<T>>> {
+    pub fn cells(&self) -> std::slice::Iter<'_, Option<Rc<T>>> {
         self.cells.iter()
     }
 
     /// Returns an iterator over references to the cells of this grid.
-    pub fn cells_mut(&mut self) -> std::slice::IterMut<Option<Rc<T>>> {
+    pub fn cells_mut(&mut self) -> std::slice::IterMut<'_, Option<Rc<T>>> {
         self.cells.iter_mut()
     }
 
@@ -403,6 +1425,64 @@ where
     }
 }
 
+/// A value that can be parsed from, and drawn as, a single character within a grid.
+pub trait Tile: Sized {
+    /// Returns the tile that the given character stands for, if there is one.
+    fn from_char(character: char) -> Option<Self>;
+
+    /// Returns the character that this tile is drawn as.
+    fn to_char(&self) -> char;
+}
+
+// Characters are the most boring tiles of all.
+impl Tile for char {
+    fn from_char(character: char) -> Option<Self> {
+        Some(character)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+impl Tile for Direction {
+    fn from_char(character: char) -> Option<Self> {
+        Self::from_char(character)
+    }
+
+    fn to_char(&self) -> char {
+        Self::to_char(*self)
+    }
+}
+
+/// Reads the file at the given path into a string, which is then converted into a grid of tiles.
+pub fn read_to_tile_grid<T, P>(path: P) -> Result<Grid2D<T>>
+where
+    T: Tile,
+    P: AsRef<Path>,
+{
+    self::string_to_tile_grid(&std::fs::read_to_string(path)?)
+}
+
+/// Converts the given string into a grid of tiles, where every cell is filled.
+///
+/// Characters that aren't any tile are reported along with their line and column.
+pub fn string_to_tile_grid<T: Tile>(string: &str) -> Result<Grid2D<T>> {
+    let characters = self::string_to_char_grid(string, |_, c| Some(c))?;
+    let mut grid = Grid2D::new(characters.size());
+
+    for (pos, character) in characters.into_iter() {
+        let Some(character) = character else { continue };
+        let Some(tile) = T::from_char(character) else {
+            return Err(Error::parse_at_column(pos.y() + 1, pos.x() + 1, format!("unknown tile {character:?}")).into());
+        };
+
+        grid.set(pos, tile);
+    }
+
+    Ok(grid)
+}
+
 /// Reads the file at the given path into a string, which is then converted into a character grid.
 pub fn read_to_char_grid<P, F>(path: P, visit_char: F) -> Result<Grid2D<char>>
 where
@@ -417,13 +1497,20 @@ pub fn string_to_char_grid<F>(string: &str, mut visit_char: F) -> Result<Grid2D<
 where
     F: FnMut(Pos2D, char) -> Option<char>,
 {
-    let w = string.lines().next().map_or(0, |v| v.len());
+    let w = string.lines().next().map_or(0, |v| v.chars().count());
     let h = string.lines().count();
-    let Some(size) = Size2D::try_new(w, h) else { bail!("invalid grid size ({w}x{h})") };
+    let Some(size) = Size2D::try_new(w, h) else {
+        return Err(Error::parse(format!("invalid grid size ({w}x{h})")).into());
+    };
 
     let mut grid = Grid2D::new(size);
 
     for (y, line) in string.lines().enumerate() {
+        // Every row has to be as wide as the first, otherwise we'd silently drop or invent cells.
+        if line.chars().count() != w {
+            return Err(Error::parse_at(y + 1, format!("expected {w} columns, found {}", line.chars().count())).into());
+        }
+
         for (x, character) in line.chars().enumerate() {
             let pos = Pos2D::new(x, y);
 
@@ -433,3 +1520,285 @@ where
 
     Ok(grid)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions_turn_and_parse() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::NorthEast.turn_around(), Direction::SouthWest);
+        assert_eq!(Direction::NorthWest.rotate(1), Direction::North);
+        assert!(Direction::cardinal().all(Direction::is_cardinal));
+        assert!(Direction::ordinal().all(Direction::is_ordinal));
+
+        for direction in Direction::all() {
+            assert_eq!(Direction::from_offset(direction.offset()), Some(direction));
+            assert_eq!(Direction::from_char(direction.to_char()), Some(direction));
+            assert_eq!(direction.to_string().parse::<Direction>().ok(), Some(direction));
+        }
+
+        assert_eq!("v".parse::<Direction>().ok(), Some(Direction::South));
+        assert_eq!("w".parse::<Direction>().ok(), Some(Direction::West));
+        assert!("up".parse::<Direction>().is_err());
+        assert_eq!(Direction::from_offset(Offset2D::new(0, 0)), None);
+    }
+
+    #[test]
+    fn neighbors_stay_in_bounds() {
+        let mut grid = Grid2D::new(Size2D::try_new(3, 2).unwrap());
+
+        grid.set(Pos2D::new(1, 0), 'a');
+
+        let corner: Vec<_> = grid.neighbors(Pos2D::new(0, 0), Connectivity::Four).collect();
+
+        assert_eq!(corner, [Pos2D::new(1, 0), Pos2D::new(0, 1)]);
+        assert_eq!(grid.neighbors(Pos2D::new(1, 1), Connectivity::Eight).count(), 5);
+        assert_eq!(grid.neighbors_with_values(Pos2D::new(0, 0), Connectivity::Four).collect::<Vec<_>>(), [
+            (Pos2D::new(1, 0), Some(&'a')),
+            (Pos2D::new(0, 1), None)
+        ]);
+
+        let knight = [Offset2D::new(2, 1), Offset2D::new(-2, -1)];
+        let moves: Vec<_> = grid.neighbors(Pos2D::new(0, 0), Connectivity::Stencil(&knight)).collect();
+
+        assert_eq!(moves, [Pos2D::new(2, 1)]);
+
+        let wrapped: Vec<_> = grid.wrapping_neighbors(Pos2D::new(0, 0), Connectivity::Four).collect();
+
+        assert_eq!(wrapped, [Pos2D::new(0, 1), Pos2D::new(1, 0), Pos2D::new(0, 1), Pos2D::new(2, 0)]);
+    }
+
+    #[test]
+    fn positions_and_offsets_do_arithmetic() {
+        let a = Pos2D::new(1, 2);
+        let b = Pos2D::new(4, 0);
+
+        assert_eq!(b - a, Offset2D::new(3, -2));
+        assert_eq!(a + (b - a), Some(b));
+        assert_eq!(a + Offset2D::new(-2, 0), None);
+        assert_eq!(b - Offset2D::new(1, 0), Some(Pos2D::new(3, 0)));
+        assert_eq!(a.manhattan_distance(b), 5);
+        assert_eq!(a.chebyshev_distance(b), 3);
+
+        let mut offset = Offset2D::new(2, -4) * 3;
+
+        offset += Offset2D::new(0, 3);
+        offset -= Offset2D::new(3, 0);
+
+        assert_eq!(offset, Offset2D::new(3, -9));
+        assert_eq!(offset.normalized(), Offset2D::new(1, -3));
+        assert_eq!(Offset2D::new(0, -5).normalized(), Offset2D::new(0, -1));
+        assert_eq!(Offset2D::default().normalized(), Offset2D::default());
+
+        // Quarter turns should agree with the compass.
+        for direction in Direction::cardinal() {
+            assert_eq!(direction.offset().rotate_right(), direction.turn_right().offset());
+            assert_eq!(direction.offset().rotate(-1), direction.turn_left().offset());
+            assert_eq!(direction.offset().rotate(6), direction.turn_around().offset());
+        }
+    }
+
+    #[test]
+    fn rays_and_lines_stay_in_bounds() {
+        let size = Size2D::try_new(5, 4).unwrap();
+        let ray: Vec<_> = Pos2D::new(1, 0).ray(Offset2D::new(1, 1), size).collect();
+
+        assert_eq!(ray, [Pos2D::new(2, 1), Pos2D::new(3, 2), Pos2D::new(4, 3)]);
+        assert_eq!(Pos2D::new(0, 0).ray(Offset2D::new(-1, 0), size).count(), 0);
+        assert_eq!(Pos2D::new(2, 2).ray(Offset2D::default(), size).count(), 0);
+
+        let mut grid = Grid2D::new(size);
+
+        grid.set(Pos2D::new(2, 0), 'a');
+
+        assert_eq!(grid.ray_values(Pos2D::new(4, 0), Offset2D::new(-2, 0)).collect::<Vec<_>>(), [
+            (Pos2D::new(2, 0), Some(&'a')),
+            (Pos2D::new(0, 0), None)
+        ]);
+
+        let segment: Vec<_> = Pos2D::line_between(Pos2D::new(4, 2), Pos2D::new(0, 0)).collect();
+
+        assert_eq!(segment, [Pos2D::new(4, 2), Pos2D::new(2, 1), Pos2D::new(0, 0)]);
+        assert_eq!(Pos2D::line_between(Pos2D::new(3, 3), Pos2D::new(3, 3)).count(), 1);
+
+        let line: Vec<_> = size.line_through(Pos2D::new(2, 1), Pos2D::new(3, 2)).collect();
+
+        assert_eq!(line, [Pos2D::new(1, 0), Pos2D::new(2, 1), Pos2D::new(3, 2), Pos2D::new(4, 3)]);
+        assert_eq!(size.line_through(Pos2D::new(9, 9), Pos2D::new(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn rows_columns_and_diagonals_read_lines() {
+        let grid = string_to_char_grid("abc\ndef\n", |_, c| Some(c)).unwrap();
+        let text = |line: &mut dyn Iterator<Item = &Option<char>>| line.flatten().collect::<String>();
+
+        assert_eq!(grid.row(1).map(|v| text(&mut v.iter())), Some("def".to_string()));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(2).map(|mut v| text(&mut v)), Some("cf".to_string()));
+        assert!(grid.column(3).is_none());
+        assert_eq!(grid.rows().map(|v| text(&mut v.iter())).collect::<Vec<_>>(), ["abc", "def"]);
+        assert_eq!(grid.columns().map(|mut v| text(&mut v)).collect::<Vec<_>>(), ["ad", "be", "cf"]);
+        assert_eq!(grid.diagonals().map(|mut v| text(&mut v)).collect::<Vec<_>>(), ["d", "ae", "bf", "c"]);
+        assert_eq!(grid.anti_diagonals().map(|mut v| text(&mut v)).collect::<Vec<_>>(), ["a", "bd", "ce", "f"]);
+
+        // Between them, every line should cover every cell exactly once.
+        assert_eq!(grid.diagonals().flatten().count(), grid.len());
+        assert_eq!(grid.anti_diagonals().flatten().count(), grid.len());
+    }
+
+    #[test]
+    fn grids_transform_and_cut() {
+        let grid = string_to_char_grid("abc\ndef\n", |_, c| Some(c)).unwrap();
+        let text =
+            |grid: &Grid2D<char>| grid.rows().map(|v| v.iter().flatten().collect::<String>()).collect::<Vec<_>>();
+
+        assert_eq!(text(&grid.transpose()), ["ad", "be", "cf"]);
+        assert_eq!(text(&grid.rotate_cw()), ["da", "eb", "fc"]);
+        assert_eq!(text(&grid.rotate_ccw()), ["cf", "be", "ad"]);
+        assert_eq!(text(&grid.flip_horizontal()), ["cba", "fed"]);
+        assert_eq!(text(&grid.flip_vertical()), ["def", "abc"]);
+        assert_eq!(text(&grid.view(Orientation::ROTATE_HALF).to_grid()), ["fed", "cba"]);
+        assert_eq!(text(&grid.view(Orientation::ANTI_TRANSPOSE).to_grid()), ["fc", "eb", "da"]);
+        assert_eq!(text(&grid.subgrid(Pos2D::new(1, 0), Size2D::try_new(2, 2).unwrap()).unwrap()), ["bc", "ef"]);
+        assert!(grid.subgrid(Pos2D::new(2, 1), Size2D::try_new(2, 1).unwrap()).is_none());
+
+        // Every orientation should be a different arrangement of the same cells.
+        let views: HashSet<_> = Orientation::ALL.iter().map(|v| text(&grid.view(*v).to_grid())).collect();
+
+        assert_eq!(views.len(), 8);
+
+        let window = grid.window(Pos2D::new(1, 0), Size2D::try_new(2, 2).unwrap()).unwrap();
+        let rotated = window.oriented(Orientation::ROTATE_CW);
+
+        assert_eq!(rotated.size(), Size2D::try_new(2, 2).unwrap());
+        assert_eq!(rotated.get(Pos2D::new(0, 0)), Some(&'e'));
+        assert_eq!(rotated.get(Pos2D::new(2, 0)), None);
+    }
+
+    #[test]
+    fn patterns_match_in_every_orientation() {
+        let grid = string_to_char_grid("ab.\n.ba\nab.\n", |_, c| Some(c)).unwrap();
+        let pattern: GridPattern<char> = "a.\n.b".parse().unwrap();
+        let found: Vec<_> = grid.find_pattern(&pattern, &[Orientation::IDENTITY]).collect();
+
+        assert_eq!(found, [(Pos2D::new(0, 0), Orientation::IDENTITY)]);
+
+        let found: Vec<_> = grid.find_pattern(&pattern, &Orientation::ALL).collect();
+
+        // The diagonal pattern is symmetric, so only four of the eight orientations are actually different.
+        assert_eq!(found.len(), 4);
+        assert!(found.contains(&(Pos2D::new(1, 1), Orientation::ROTATE_CW)));
+        assert!(found.contains(&(Pos2D::new(1, 0), Orientation::ROTATE_HALF)));
+        assert!(found.contains(&(Pos2D::new(0, 1), Orientation::ROTATE_CCW)));
+        assert!(found.iter().all(|(_, v)| !v.is_flipped()));
+
+        let large: GridPattern<char> = "....\n....".parse().unwrap();
+
+        let wildcards: GridPattern<char> = "...\n...".parse().unwrap();
+
+        assert_eq!(grid.find_pattern(&large, &Orientation::ALL).count(), 0);
+        assert_eq!(grid.find_pattern(&wildcards, &[Orientation::ROTATE_CW]).count(), 2);
+        assert!("ab\nc".parse::<GridPattern<char>>().is_err());
+    }
+
+    #[test]
+    fn grids_display_and_parse() {
+        let text = "#.^\n..#";
+        let grid: Grid2D<char> = text.parse().unwrap();
+
+        assert_eq!(grid.get(Pos2D::new(1, 0)), None);
+        assert_eq!(grid.to_string(), text);
+        assert_eq!(grid.to_string().parse::<Grid2D<char>>().unwrap(), grid);
+        assert_eq!(grid.render(' '), "# ^\n  #");
+
+        let mut numbers = Grid2D::new(Size2D::try_new(2, 2).unwrap());
+
+        numbers.set(Pos2D::new(0, 0), 7_u8);
+        numbers.set(Pos2D::new(1, 1), 12);
+
+        let rendered = numbers.render_with(|v| v.map_or('?', |v| char::from_digit(u32::from(*v), 16).unwrap_or('!')));
+
+        assert_eq!(rendered, "7?\n?c");
+        assert!("ab\nc".parse::<Grid2D<char>>().is_err());
+    }
+
+    #[test]
+    fn tiles_parse_and_render() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        enum Cell {
+            Wall,
+            Floor,
+        }
+
+        impl Tile for Cell {
+            fn from_char(character: char) -> Option<Self> {
+                match character {
+                    '#' => Some(Self::Wall),
+                    '.' => Some(Self::Floor),
+                    _ => None,
+                }
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    Self::Wall => '#',
+                    Self::Floor => '.',
+                }
+            }
+        }
+
+        let grid = string_to_tile_grid::<Cell>("#.\n.#\n").unwrap();
+
+        assert_eq!(grid.get(Pos2D::new(1, 0)), Some(&Cell::Floor));
+        assert_eq!(grid.to_string(), "#.\n.#");
+
+        let error = string_to_tile_grid::<Cell>("#.\n.x\n").unwrap_err();
+
+        assert_eq!(error.to_string(), "invalid input at line 2, column 2: unknown tile 'x'");
+
+        let arrows = string_to_tile_grid::<Direction>("^>\nv<").unwrap();
+
+        assert_eq!(arrows.get(Pos2D::new(1, 1)), Some(&Direction::West));
+        assert_eq!(arrows.to_string(), "^>\nv<");
+    }
+
+    #[test]
+    fn dense_grids_index_and_convert() {
+        let size = Size2D::try_new(3, 2).unwrap();
+        let mut heights = DenseGrid2D::from_fn(size, |v| (v.x() + v.y() * 3) as u8);
+
+        assert_eq!(heights[Pos2D::new(2, 1)], 5);
+
+        heights[Pos2D::new(0, 1)] += 10;
+        *heights.get_mut(Pos2D::new(1, 0)).unwrap() = 9;
+
+        assert_eq!(heights.set(Pos2D::new(2, 0), 0), Some(2));
+        assert_eq!(heights.set(Pos2D::new(3, 0), 0), None);
+        assert_eq!(heights.get(Pos2D::new(0, 2)), None);
+        assert_eq!(heights.rows().collect::<Vec<_>>(), [&[0, 9, 0][..], &[13, 4, 5][..]]);
+
+        let mut visited = Grid2D::new(size);
+
+        visited.set(Pos2D::new(1, 1), true);
+
+        let dense = DenseGrid2D::from_grid(visited, false);
+
+        assert_eq!(dense.iter().filter(|(_, v)| **v).map(|(p, _)| p).collect::<Vec<_>>(), [Pos2D::new(1, 1)]);
+        assert_eq!(Grid2D::from(dense.clone()).cells().filter(|v| v.is_some()).count(), dense.len());
+        assert_eq!(
+            DenseGrid2D::from_grid(dense.into_grid(), true),
+            DenseGrid2D::from_fn(size, |v| v == Pos2D::new(1, 1))
+        );
+    }
+
+    #[test]
+    #[should_panic = "position (3, 0) is outside of the grid's 3x2 size"]
+    fn dense_grids_panic_out_of_bounds() {
+        let grid = DenseGrid2D::new(Size2D::try_new(3, 2).unwrap(), 0);
+
+        _ = grid[Pos2D::new(3, 0)];
+    }
+}
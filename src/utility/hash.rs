@@ -0,0 +1,28 @@
+/// The FNV-1a 64-bit offset basis.
+const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+/// The FNV-1a 64-bit prime.
+const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Hashes the given bytes using 64-bit FNV-1a.
+///
+/// Unlike the standard library's hashers, this is guaranteed to produce the same value across runs, platforms, and
+/// compiler versions, so it's safe to write to disk. It is *not* safe against anyone actively trying to cause
+/// collisions, but nobody is going to do that to a puzzle input.
+pub const fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = OFFSET_BASIS;
+    let mut index = 0;
+
+    // Iterators aren't allowed within constant functions yet, so we're stuck doing this the old-fashioned way.
+    while index < bytes.len() {
+        hash ^= bytes[index] as u64;
+        hash = hash.wrapping_mul(PRIME);
+        index += 1;
+    }
+
+    hash
+}
+
+/// Hashes the given bytes using 64-bit FNV-1a, returning the hash as a hexadecimal string.
+pub fn fnv1a_hex(bytes: &[u8]) -> String {
+    format!("{:016x}", self::fnv1a(bytes))
+}
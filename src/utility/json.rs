@@ -0,0 +1,542 @@
+use std::fmt::{Display, Write};
+
+use anyhow::{Result, anyhow};
+
+/// The deepest that arrays and objects may be nested while parsing, which keeps hostile input from blowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// A JSON value.
+///
+/// Objects keep their keys in insertion order, which keeps any output stable and easy to read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    String(String),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+
+impl Json {
+    /// Creates a new JSON object from the given entries.
+    pub fn object<K, V>(entries: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<Self>,
+    {
+        Self::Object(entries.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+
+    /// Parses the given JSON text into a value.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser { source, position: 0 };
+        let value = parser.value(0)?;
+
+        parser.skip_whitespace();
+
+        if parser.position < source.len() {
+            Err(parser.error("unexpected characters after the value"))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Returns the value of the given key, if this is an object that contains it.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        let Self::Object(entries) = self else { return None };
+
+        entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Returns this value as an integer, if it is one.
+    pub const fn as_integer(&self) -> Option<i128> {
+        if let Self::Integer(value) = self { Some(*value) } else { None }
+    }
+
+    /// Returns this value as a string, if it is one.
+    pub const fn as_str(&self) -> Option<&str> {
+        if let Self::String(value) = self { Some(value.as_str()) } else { None }
+    }
+
+    /// Returns this value's elements, if it's an array.
+    pub const fn as_array(&self) -> Option<&[Self]> {
+        if let Self::Array(elements) = self { Some(elements.as_slice()) } else { None }
+    }
+
+    /// Returns this value's entries, if it's an object.
+    pub const fn as_object(&self) -> Option<&[(String, Self)]> {
+        if let Self::Object(entries) = self { Some(entries.as_slice()) } else { None }
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            // JSON doesn't have any way to represent these, so we do what everyone else does.
+            Self::Float(value) if !value.is_finite() => f.write_str("null"),
+            // Debug formatting always keeps a decimal point or exponent, so the value reads back as a float.
+            Self::Float(value) => write!(f, "{value:?}"),
+            Self::String(value) => self::write_escaped(f, value),
+            Self::Array(values) => {
+                f.write_char('[')?;
+
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    write!(f, "{value}")?;
+                }
+
+                f.write_char(']')
+            }
+            Self::Object(entries) => {
+                f.write_char('{')?;
+
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    self::write_escaped(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<T: Into<Self>> From<Vec<T>> for Json {
+    fn from(value: Vec<T>) -> Self {
+        Self::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+// Every integer type gets the same treatment, so we may as well let a macro do the typing.
+macro_rules! impl_from_integer {
+    ($($type:ty),+ $(,)?) => {$(
+        impl From<$type> for Json {
+            fn from(value: $type) -> Self {
+                Self::Integer(value as i128)
+            }
+        }
+    )+};
+}
+
+impl_from_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Writes the given string as a quoted and escaped JSON string.
+fn write_escaped(f: &mut impl Write, string: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+
+    for character in string.chars() {
+        match character {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+/// A tiny recursive descent parser, which is about all that JSON needs.
+struct Parser<'s> {
+    /// The text being parsed.
+    source: &'s str,
+    /// The byte offset of the next unread character.
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Creates an error pointing at the current position.
+    fn error(&self, message: impl Display) -> anyhow::Error {
+        anyhow!("invalid JSON at byte {}: {message}", self.position)
+    }
+
+    /// Returns the next unread byte without consuming it.
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    /// Consumes the next byte if it's the given one, returning whether it was.
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+
+        self.position += usize::from(found);
+
+        found
+    }
+
+    /// Consumes the given byte, failing if it's not next.
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.eat(byte) { Ok(()) } else { Err(self.error(format!("expected '{}'", byte as char))) }
+    }
+
+    /// Skips over any whitespace.
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    /// Parses any value.
+    fn value(&mut self, depth: usize) -> Result<Json> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("values are nested too deeply"));
+        }
+
+        self.skip_whitespace();
+
+        match self.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => self.array(depth),
+            Some(b'{') => self.object(depth),
+            Some(b'-' | b'0' ..= b'9') => self.number(),
+            Some(byte) => Err(self.error(format!("unexpected character '{}'", byte.escape_ascii()))),
+        }
+    }
+
+    /// Parses one of the bare words, returning the given value if it's there.
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json> {
+        if !self.source[self.position ..].starts_with(keyword) {
+            return Err(self.error(format!("expected '{keyword}'")));
+        }
+
+        self.position += keyword.len();
+
+        Ok(value)
+    }
+
+    /// Parses an array.
+    fn array(&mut self, depth: usize) -> Result<Json> {
+        let mut values = Vec::new();
+
+        self.expect(b'[')?;
+        self.skip_whitespace();
+
+        if self.eat(b']') {
+            return Ok(Json::Array(values));
+        }
+
+        loop {
+            values.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+
+            if self.eat(b']') {
+                return Ok(Json::Array(values));
+            }
+
+            self.expect(b',')?;
+        }
+    }
+
+    /// Parses an object.
+    fn object(&mut self, depth: usize) -> Result<Json> {
+        let mut entries = Vec::new();
+
+        self.expect(b'{')?;
+        self.skip_whitespace();
+
+        if self.eat(b'}') {
+            return Ok(Json::Object(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            let key = self.string()?;
+
+            self.skip_whitespace();
+            self.expect(b':')?;
+            entries.push((key, self.value(depth + 1)?));
+            self.skip_whitespace();
+
+            if self.eat(b'}') {
+                return Ok(Json::Object(entries));
+            }
+
+            self.expect(b',')?;
+        }
+    }
+
+    /// Parses a number, which stays an integer unless it has a fraction or an exponent.
+    fn number(&mut self) -> Result<Json> {
+        let start = self.position;
+
+        self.eat(b'-');
+        self.digits()?;
+
+        let fraction = self.eat(b'.');
+
+        if fraction {
+            self.digits()?;
+        }
+
+        let exponent = self.eat(b'e') || self.eat(b'E');
+
+        if exponent {
+            _ = self.eat(b'+') || self.eat(b'-');
+            self.digits()?;
+        }
+
+        // Everything consumed so far is ASCII, so this can't land in the middle of a character.
+        let text = &self.source[start .. self.position];
+
+        if !fraction
+            && !exponent
+            && let Ok(value) = text.parse()
+        {
+            return Ok(Json::Integer(value));
+        }
+
+        // Integers too large for us still make perfectly fine (if imprecise) floats.
+        text.parse().map(Json::Float).map_err(|_| self.error(format!("invalid number '{text}'")))
+    }
+
+    /// Consumes at least one digit.
+    fn digits(&mut self) -> Result<()> {
+        let start = self.position;
+
+        while self.peek().is_some_and(|v| v.is_ascii_digit()) {
+            self.position += 1;
+        }
+
+        if self.position == start { Err(self.error("expected a digit")) } else { Ok(()) }
+    }
+
+    /// Parses a string, resolving any escapes.
+    fn string(&mut self) -> Result<String> {
+        let mut string = String::new();
+
+        self.expect(b'"')?;
+
+        loop {
+            let start = self.position;
+
+            // Plain characters are copied over in runs. Every byte that stops a run is ASCII, so the slice is always
+            // valid.
+            while self.peek().is_some_and(|v| v != b'"' && v != b'\\' && v >= 0x20) {
+                self.position += 1;
+            }
+
+            string.push_str(&self.source[start .. self.position]);
+
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => {
+                    self.position += 1;
+
+                    return Ok(string);
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+
+                    let character = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.position += 1;
+
+                            string.push(self.unicode_escape()?);
+
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+
+                    self.position += 1;
+                    string.push(character);
+                }
+                Some(_) => return Err(self.error("unescaped control character within string")),
+            }
+        }
+    }
+
+    /// Parses the digits of a `\u` escape, along with the second half of a surrogate pair if there is one.
+    fn unicode_escape(&mut self) -> Result<char> {
+        let high = self.hex_digits()?;
+
+        if !(0xD800 .. 0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("unpaired surrogate"));
+        }
+
+        if !self.source[self.position ..].starts_with("\\u") {
+            return Err(self.error("unpaired surrogate"));
+        }
+
+        self.position += 2;
+
+        let low = self.hex_digits()?;
+
+        if !(0xDC00 .. 0xE000).contains(&low) {
+            return Err(self.error("invalid surrogate pair"));
+        }
+
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| self.error("invalid surrogate pair"))
+    }
+
+    /// Parses exactly four hexadecimal digits.
+    fn hex_digits(&mut self) -> Result<u32> {
+        let digits =
+            self.source.get(self.position .. self.position + 4).filter(|v| v.bytes().all(|v| v.is_ascii_hexdigit()));
+        let Some(digits) = digits else { return Err(self.error("expected four hexadecimal digits")) };
+
+        self.position += 4;
+
+        // They were all just checked, so this can't actually fail.
+        Ok(u32::from_str_radix(digits, 16).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the given text, panicking if it isn't valid.
+    fn parse(source: &str) -> Json {
+        Json::parse(source).unwrap_or_else(|error| panic!("{source:?} should parse: {error}"))
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let value = Json::object([
+            ("null", Json::Null),
+            ("bool", Json::Bool(true)),
+            ("integer", Json::Integer(-170_141_183_460_469_231_731_687_303_715_884_105_728)),
+            ("float", Json::Float(1.0)),
+            ("tiny", Json::Float(1e-300)),
+            ("string", Json::from("quote \" slash \\ tab \t bell \u{7} snowman \u{2603}")),
+            ("array", Json::from(vec![1, 2, 3])),
+            ("nested", Json::object([("empty", Json::Array(Vec::new()))])),
+        ]);
+
+        assert_eq!(parse(&value.to_string()), value);
+    }
+
+    #[test]
+    fn numbers_keep_their_kind() {
+        assert_eq!(parse("42"), Json::Integer(42));
+        assert_eq!(parse("-0"), Json::Integer(0));
+        assert_eq!(parse("1.5"), Json::Float(1.5));
+        assert_eq!(parse("2e3"), Json::Float(2000.0));
+        // Anything past an `i128` is still a number, just not an exact one.
+        assert_eq!(parse("340282366920938463463374607431768211456"), Json::Float(2.0_f64.powi(128)));
+        assert_eq!(Json::Float(f64::NAN).to_string(), "null");
+    }
+
+    #[test]
+    fn escapes_are_resolved() {
+        assert_eq!(parse(r#""a\/\"\\\n\u00e9""#), Json::from("a/\"\\\né"));
+        // U+1F600 only fits in UTF-16 as a surrogate pair.
+        assert_eq!(parse(r#""\ud83d\ude00""#), Json::from("\u{1f600}"));
+    }
+
+    #[test]
+    fn whitespace_is_allowed_between_tokens() {
+        let value = parse(" {\n\t\"a\" : [ 1 , { } ] ,\r\n \"b\":null } ");
+
+        assert_eq!(value.get("a").and_then(Json::as_array).map(<[_]>::len), Some(2));
+        assert_eq!(value.get("b"), Some(&Json::Null));
+    }
+
+    #[test]
+    fn invalid_text_is_rejected() {
+        let invalid = [
+            "",
+            "nul",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{a: 1}",
+            "1 2",
+            "-",
+            "1.",
+            "1e",
+            "\"unterminated",
+            "\"tab\tinside\"",
+            r#""\x""#,
+            r#""\u12""#,
+            r#""\ud83d""#,
+            r#""\ud83d\u0041""#,
+            r#""\ude00""#,
+        ];
+
+        for source in invalid {
+            assert!(Json::parse(source).is_err(), "{source:?} shouldn't parse");
+        }
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+        assert!(Json::parse(&nested(MAX_DEPTH + 1)).is_ok());
+        assert!(Json::parse(&nested(MAX_DEPTH + 2)).is_err());
+    }
+
+    #[test]
+    fn accessors_check_the_kind() {
+        let value = parse(r#"{"a": 1, "b": "two", "a": 3}"#);
+
+        // The first of any repeated keys wins.
+        assert_eq!(value.get("a").and_then(Json::as_integer), Some(1));
+        assert_eq!(value.get("b").and_then(Json::as_str), Some("two"));
+        assert_eq!(value.get("b").and_then(Json::as_integer), None);
+        assert_eq!(value.get("c"), None);
+        assert_eq!(Json::Null.get("a"), None);
+        assert_eq!(value.as_object().map(<[_]>::len), Some(3));
+    }
+}
@@ -0,0 +1,67 @@
+/// A small, deterministic pseudo-random number generator.
+///
+/// This is an implementation of SplitMix64, which is nowhere near cryptographically secure, but is fast, tiny, and
+/// always produces the same sequence for the same seed. That's all we need for generating puzzle inputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Random {
+    /// The generator's internal state.
+    state: u64,
+}
+
+impl Random {
+    /// Creates a new [`Random`] using the given seed.
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next random 64-bit value.
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut value = self.state;
+
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        value ^ (value >> 31)
+    }
+
+    /// Returns a random value within `0 .. bound`.
+    ///
+    /// This will return zero if the given bound is zero.
+    pub const fn below(&mut self, bound: u64) -> u64 {
+        // Multiplying into the upper half avoids most of the bias that a plain modulo would have, and we really don't
+        // care about the tiny amount left over.
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    /// Returns a random value within `min ..= max`.
+    pub const fn between(&mut self, min: u64, max: u64) -> u64 {
+        assert!(min <= max, "the minimum must not be greater than the maximum");
+
+        match (max - min).checked_add(1) {
+            Some(bound) => min + self.below(bound),
+            None => self.next_u64(),
+        }
+    }
+
+    /// Returns `true` with a probability of `numerator / denominator`.
+    pub const fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    /// Returns a reference to a random element of the given slice.
+    ///
+    /// This will return `None` if the slice is empty.
+    pub fn choose<'s, T>(&mut self, slice: &'s [T]) -> Option<&'s T> {
+        slice.get(self.below(slice.len() as u64) as usize)
+    }
+
+    /// Shuffles the given slice in place.
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        // Fisher-Yates, the one and only.
+        for index in (1 .. slice.len()).rev() {
+            slice.swap(index, self.below(index as u64 + 1) as usize);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Runs every day's solutions against its example input, along with the real input whenever one is present.
+//!
+//! The crate is just a binary, so everything here goes through the executable exactly like a person would.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// The path of the compiled executable.
+const EXECUTABLE: &str = env!("CARGO_BIN_EXE_advent-of-code-2024");
+/// The environment variable containing the key used to unseal inputs.
+const KEY_VARIABLE: &str = "AOC_INPUT_KEY";
+/// The file containing the expected answers for the real inputs, in the same format that `batch --answers` reads.
+const ANSWERS_FILE: &str = "data/answers.txt";
+
+/// Generates a test that checks the answer of one part of one day against its example input.
+macro_rules! example {
+    ($($(#[$attribute:meta])* $name:ident: $day:literal, $part:literal => $expected:literal;)+) => {$(
+        $(#[$attribute])*
+        #[test]
+        fn $name() {
+            self::assert_answer($day, $part, true, $expected);
+        }
+    )+};
+}
+
+/// Generates a test that runs the given parts of one day against its real input, if there is one.
+macro_rules! real {
+    ($($(#[$attribute:meta])* $name:ident: $day:literal => [$($part:literal),+];)+) => {$(
+        $(#[$attribute])*
+        #[test]
+        fn $name() {
+            self::check_real($day, &[$($part),+]);
+        }
+    )+};
+}
+
+example! {
+    day_01_part_1_example: 1, 1 => 11;
+    day_01_part_2_example: 1, 2 => 31;
+    day_02_part_1_example: 2, 1 => 2;
+    day_02_part_2_example: 2, 2 => 4;
+    day_03_part_1_example: 3, 1 => 161;
+    day_03_part_2_example: 3, 2 => 48;
+    day_04_part_1_example: 4, 1 => 18;
+    day_04_part_2_example: 4, 2 => 9;
+    day_05_part_1_example: 5, 1 => 143;
+    day_05_part_2_example: 5, 2 => 123;
+    day_06_part_1_example: 6, 1 => 41;
+    day_06_part_2_example: 6, 2 => 6;
+    day_07_part_1_example: 7, 1 => 3749;
+    day_07_part_2_example: 7, 2 => 11387;
+    day_08_part_1_example: 8, 1 => 14;
+    day_08_part_2_example: 8, 2 => 34;
+    day_09_part_1_example: 9, 1 => 1928;
+    #[ignore = "day 9 part 2 hasn't been solved yet"]
+    day_09_part_2_example: 9, 2 => 2858;
+}
+
+real! {
+    day_01_real: 1 => [1, 2];
+    day_02_real: 2 => [1, 2];
+    day_03_real: 3 => [1, 2];
+    day_04_real: 4 => [1, 2];
+    day_05_real: 5 => [1, 2];
+    day_06_real: 6 => [1, 2];
+    day_07_real: 7 => [1, 2];
+    day_08_real: 8 => [1, 2];
+    // Part 2 would only panic, same as its example.
+    day_09_real: 9 => [1];
+}
+
+/// A part that doesn't exist is reported as such, even when the real input it'd need isn't there either.
+#[test]
+fn missing_part_before_input() {
+    let output = self::run(&["7", "3", "0", "--input", "data/day_0.txt"]);
+
+    assert_eq!(output.status.code(), Some(3), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+/// Runs the executable with the given arguments from the root of the repository, so that `./data` can be found.
+fn run(arguments: &[&str]) -> Output {
+    Command::new(EXECUTABLE)
+        .args(arguments)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        // A stale answer from the cache would defeat the entire point.
+        .env_remove("AOC_CACHE")
+        .output()
+        .expect("failed to run the executable")
+}
+
+/// Runs one part of one day, returning its answer or a description of what went wrong.
+fn solve(day: u8, part: u8, example: bool) -> Result<u64, String> {
+    let output = self::run(&[&day.to_string(), &part.to_string(), if example { "1" } else { "0" }]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if !output.status.success() {
+        return Err(format!("exited with {}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    stdout.trim().parse().map_err(|_| format!("printed something that isn't an answer: {stdout:?}"))
+}
+
+/// Asserts that one part of one day gives the expected answer.
+fn assert_answer(day: u8, part: u8, example: bool, expected: u64) {
+    match self::solve(day, part, example) {
+        Ok(answer) => assert_eq!(answer, expected, "day {day} part {part} gave the wrong answer"),
+        Err(error) => panic!("day {day} part {part} failed: {error}"),
+    }
+}
+
+/// Runs the given parts of one day against its real input if it's present, checking them against any known answers.
+///
+/// Real inputs aren't committed, so they're skipped whenever they're missing rather than failing the test.
+fn check_real(day: u8, parts: &[u8]) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("data/day_{day}.txt"));
+
+    if !self::is_available(&path) {
+        eprintln!("skipping day {day}, since '{}' is not available", path.display());
+
+        return;
+    }
+
+    let answers = self::read_answers(&format!("day_{day}.txt"));
+
+    for &part in parts {
+        match answers[part as usize - 1] {
+            Some(expected) => self::assert_answer(day, part, false, expected),
+            // Without a known answer, the best we can do is make sure that it doesn't fall over.
+            None => {
+                if let Err(error) = self::solve(day, part, false) {
+                    panic!("day {day} part {part} failed: {error}");
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether the input file at the given path can be read, either directly or by unsealing it.
+fn is_available(path: &Path) -> bool {
+    let mut sealed = path.as_os_str().to_os_string();
+
+    sealed.push(".sealed");
+
+    path.exists() || (Path::new(&sealed).exists() && std::env::var_os(KEY_VARIABLE).is_some())
+}
+
+/// Returns the known answers for the input file with the given name, if any were written down.
+fn read_answers(name: &str) -> [Option<u64>; 2] {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_FILE);
+    let Ok(contents) = std::fs::read_to_string(path) else { return [None; 2] };
+
+    for line in contents.lines().filter(|v| !v.starts_with('#')) {
+        let mut columns = line.split_whitespace();
+
+        if columns.next() == Some(name) {
+            // Anything written as `-` (or otherwise not a number) just isn't known yet.
+            return [columns.next(), columns.next()].map(|v| v.and_then(|v| v.parse().ok()));
+        }
+    }
+
+    [None; 2]
+}
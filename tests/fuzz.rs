@@ -0,0 +1,23 @@
+//! Runs the fuzz harness against every day, so that any parser that starts panicking on bad input fails the build.
+
+use std::process::Command;
+
+/// The path of the compiled executable.
+const EXECUTABLE: &str = env!("CARGO_BIN_EXE_advent-of-code-2024");
+
+#[test]
+fn parsers_never_panic() {
+    let corpus = format!("{}/fuzz-corpus", env!("CARGO_TARGET_TMPDIR"));
+    let output = Command::new(EXECUTABLE)
+        .args(["fuzz", "--corpus", &corpus])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("failed to run the executable");
+
+    assert!(
+        output.status.success(),
+        "the fuzz harness found a failing case:\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}